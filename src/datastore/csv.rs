@@ -1,41 +1,358 @@
 use crate::{
     interface::log::Log,
-    models::comparison_data::ComparisonData,
-    datastore::transformer::sqlite_row_to_string_vec,
+    models::{
+        comparison_data::ComparisonData,
+        value::Value,
+        result_row::ResultRow,
+        table_data::{
+            ColumnData,
+            TableData,
+        },
+    },
+    datastore::{
+        data_source::DataSource,
+        sqlite,
+    },
 };
+use std::sync::OnceLock;
 
-pub fn export_comparison_data_to_csv(result: &ComparisonData, output_file_name: &str, log: &Log) {
-    if !result.unique_table_1_rows.is_empty() {
-        let unique_table_1_row_file_name = format!("unique_table_1_rows_{}", output_file_name);
-        let mut unique_writer = csv::Writer::from_path(unique_table_1_row_file_name).unwrap();
-        for row in result.unique_table_1_rows.iter(){
-            let row = sqlite_row_to_string_vec(row, log);
-            unique_writer.write_record(row).unwrap();
+/// Data source that reads a table out of a csv file with a header row
+pub(crate) struct CsvSource {
+    /// path to the csv file
+    pub file_path: String,
+    /// header and records of the file, read the first time they are needed
+    contents: OnceLock<(Vec<String>, Vec<csv::StringRecord>)>,
+}
+
+impl CsvSource {
+    pub fn new(file_path: &str) -> CsvSource {
+        CsvSource {
+            file_path: file_path.to_string(),
+            contents: OnceLock::new(),
+        }
+    }
+
+    /// the header and every record of the csv file, which is only read and
+    /// parsed once no matter how often it is asked for
+    fn read_file(&self) -> &(Vec<String>, Vec<csv::StringRecord>) {
+        self.contents.get_or_init(|| self.parse_file())
+    }
+
+    /// read the header and every record out of the csv file
+    fn parse_file(&self) -> (Vec<String>, Vec<csv::StringRecord>) {
+        let mut reader = match csv::Reader::from_path(&self.file_path) {
+            Ok(reader) => reader,
+            Err(error) => {
+                panic!("unable to open csv file {}: {}", self.file_path, error);
+            }
+        };
+
+        let headers = match reader.headers() {
+            Ok(headers) => headers.iter().map(sanitize_name).collect(),
+            Err(error) => {
+                panic!("unable to read header row of {}: {}", self.file_path, error);
+            }
+        };
+
+        let mut records = Vec::new();
+        for record in reader.records() {
+            match record {
+                Ok(record) => records.push(record),
+                Err(error) => {
+                    panic!("unable to read record from {}: {}", self.file_path, error);
+                }
+            }
+        }
+        (headers, records)
+    }
+}
+
+impl DataSource for CsvSource {
+    /// column names come from the header row and the types are inferred from
+    /// every row of the file, so a value far down the file can't fail to fit
+    /// the type picked for its column
+    async fn get_columns(&self, _table_name: &str, log: &Log) -> Vec<ColumnData> {
+        let (headers, records) = self.read_file();
+
+        let mut columns = Vec::new();
+        for (i, header) in headers.iter().enumerate() {
+            let values: Vec<&str> = records.iter().filter_map(|record| record.get(i)).collect();
+            let data_type = infer_column_type(&values);
+            log.debug(&format!("inferred type {} for csv column {}", data_type, header));
+            columns.push(ColumnData::new(header.clone(), data_type));
+        }
+        columns
+    }
+
+    /// csv files have no notion of a key so the first column is used
+    async fn get_primary_key(&self, _table_name: &str, _log: &Log) -> Vec<String> {
+        let (headers, _) = self.read_file();
+        match headers.first() {
+            Some(header) => vec![header.clone()],
+            None => {
+                panic!("csv file {} has no columns", self.file_path);
+            }
+        }
+    }
+
+    /// a csv file holds a single table, named after the file
+    fn source_table(&self, _table_name: &str) -> (String, String) {
+        let file_stem = std::path::Path::new(&self.file_path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(&self.file_path);
+        ("".to_string(), file_stem.to_string())
+    }
+
+    /// csv files can't be queried so the whole file is loaded into sqlite, a
+    /// query passed in for a csv file is rejected by the argument parser
+    async fn load_rows(&self, _query: &str, table_data: &TableData, log: &Log) {
+        let (_, records) = self.read_file();
+        let sqlite_pool = sqlite::get_connection(&table_data.sqlite_file_name, log).await;
+        sqlite::create_staging_table(&table_data.table_name, &table_data.columns, &sqlite_pool).await;
+        log.info(&format!("created new sqlite table: {}", &table_data.table_name));
+
+        // empty fields are treated as null
+        let rows: Vec<Vec<Option<String>>> = records
+            .iter()
+            .map(|record| {
+                (0..table_data.columns.len())
+                    .map(|i| record.get(i).filter(|value| !value.is_empty()).map(str::to_string))
+                    .collect()
+            })
+            .collect();
+        sqlite::insert_staging_rows(&table_data.table_name, &table_data.columns, &rows, &sqlite_pool).await;
+        log.info(&format!("loaded {} rows from {}", records.len(), self.file_path));
+    }
+}
+
+/// infer the sqlite type of a column from its values. Empty values
+/// are treated as null and do not affect the result
+pub fn infer_column_type(values: &[&str]) -> String {
+    let values: Vec<&&str> = values.iter().filter(|value| !value.is_empty()).collect();
+    if values.is_empty() {
+        return "TEXT".to_string();
+    }
+
+    if values.iter().all(|value| value.parse::<i64>().is_ok()) {
+        "INTEGER".to_string()
+    } else if values.iter().all(|value| value.parse::<f64>().is_ok()) {
+        "REAL".to_string()
+    } else {
+        "TEXT".to_string()
+    }
+}
+
+/// convert a file or column name into something that can be used as an
+/// unquoted sqlite identifier
+pub fn sanitize_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    if sanitized.is_empty() || sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert_str(0, "t_");
+    }
+    sanitized
+}
+
+/// Formatting options for the csv output
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    /// byte written between fields
+    pub delimiter: u8,
+    /// when fields are wrapped in quotes
+    pub quote_style: csv::QuoteStyle,
+    /// text written in place of null values
+    pub null_value: String,
+    /// line ending written after each record
+    pub terminator: csv::Terminator,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            quote_style: csv::QuoteStyle::Necessary,
+            null_value: String::new(),
+            terminator: csv::Terminator::CRLF,
         }
+    }
+}
+
+impl CsvOptions {
+    /// open a csv writer for the file using these options
+    fn writer(&self, file_path: &str) -> Result<csv::Writer<std::fs::File>, csv::Error> {
+        csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .quote_style(self.quote_style)
+            .terminator(self.terminator)
+            .from_path(file_path)
+    }
 
-        unique_writer.flush().unwrap();
-        drop(unique_writer);
+    /// format a value as a csv field, writing nulls as the null value
+    fn field(&self, value: Option<&Value>) -> String {
+        value
+            .and_then(Value::as_string)
+            .unwrap_or_else(|| self.null_value.clone())
     }
+}
 
-    if !result.unique_table_2_rows.is_empty() {
-        let unique_table_2_row_file_name = format!("unique_table_2_rows_{}", output_file_name);
-        let mut unique_writer2 = csv::Writer::from_path(unique_table_2_row_file_name).unwrap();
-        for row in result.unique_table_2_rows.iter(){
-            let row = sqlite_row_to_string_vec(row, log);
-            unique_writer2 .write_record(row).unwrap();
+/// write rows that only exist in one of the tables to a csv file, with a
+/// header row of the column names
+fn write_unique_rows(file_name: &str, rows: &[ResultRow], options: &CsvOptions) -> Result<(), csv::Error> {
+    let Some(first_row) = rows.first() else {
+        return Ok(());
+    };
+
+    let mut writer = options.writer(file_name)?;
+    writer.write_record(&first_row.columns)?;
+    for row in rows.iter() {
+        for value in row.values.iter() {
+            writer.write_field(options.field(Some(value)))?;
         }
-        unique_writer2.flush().unwrap();
-        drop(unique_writer2);
+        writer.write_record(None::<&[u8]>)?;
     }
+    writer.flush()?;
+    Ok(())
+}
+
+/// name of one of the split output files, which is the output file name with a
+/// prefix added, kept in the same directory as the output file
+fn prefixed_file_name(prefix: &str, output_file_name: &str) -> String {
+    let output_path = std::path::Path::new(output_file_name);
+    let file_name = output_path
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or(output_file_name);
+    output_path
+        .with_file_name(format!("{}{}", prefix, file_name))
+        .to_string_lossy()
+        .to_string()
+}
 
+/// write the differences to separate csv files for the rows unique to each
+/// table and the changed rows, each with a header row
+pub fn export_comparison_data_to_csv(
+    result: &ComparisonData,
+    output_file_name: &str,
+    options: &CsvOptions,
+    log: &Log,
+) -> Result<(), csv::Error> {
+    write_unique_rows(
+        &prefixed_file_name("unique_table_1_rows_", output_file_name),
+        &result.unique_table_1_rows,
+        options,
+    )?;
+    write_unique_rows(
+        &prefixed_file_name("unique_table_2_rows_", output_file_name),
+        &result.unique_table_2_rows,
+        options,
+    )?;
+
+    // changed rows are written one line per differing column as
+    // key values..., column, table 1 value, table 2 value
     if !result.changed_rows.is_empty() {
-        let changed_rows_file_name = format!("changed_rows_{}", output_file_name);
-        let mut changed_writer = csv::Writer::from_path(changed_rows_file_name).unwrap();
+        let changed_rows_file_name = prefixed_file_name("changed_rows_", output_file_name);
+        let mut changed_writer = options.writer(&changed_rows_file_name)?;
+
+        let mut header: Vec<&str> = result.changed_rows[0].key_values.iter().map(|(key, _)| key.as_str()).collect();
+        header.extend(["column", "table_1_value", "table_2_value"]);
+        changed_writer.write_record(&header)?;
+
         for row in result.changed_rows.iter(){
-            let row = sqlite_row_to_string_vec(row, log);
-            changed_writer.write_record(row).unwrap();
+            for difference in row.differences.iter() {
+                for (_, value) in row.key_values.iter() {
+                    changed_writer.write_field(options.field(Some(value)))?;
+                }
+                changed_writer.write_field(&difference.column)?;
+                changed_writer.write_field(options.field(Some(&difference.left_value)))?;
+                changed_writer.write_field(options.field(Some(&difference.right_value)))?;
+                changed_writer.write_record(None::<&[u8]>)?;
+            }
         }
-        changed_writer.flush().unwrap();
-        drop(changed_writer);
+        changed_writer.flush()?;
     }
+    log.info(&format!("exported comparison data to {}", output_file_name));
+    Ok(())
+}
+
+/// written in the unified csv on the side of a row that only exists in the
+/// other table
+pub const UNIFIED_MISSING_VALUE: &str = "<missing>";
+/// write every difference to a single csv file with a header row. Each line
+/// has a `_change` column (only_left, only_right or changed), the key columns
+/// and a `column__left`/`column__right` pair for every compared column. Nulls
+/// are written as the null value of the options and the side a row is missing
+/// from as `<missing>`. Changed rows show the value of every column on both
+/// sides, the ones that didn't change included
+pub fn export_comparison_data_to_unified_csv(
+    result: &ComparisonData,
+    output_file_name: &str,
+    options: &CsvOptions,
+    log: &Log,
+) -> Result<(), csv::Error> {
+    let mut writer = options.writer(output_file_name)?;
+    let pairs = &result.column_mapping.pairs;
+
+    let mut header = vec!["_change".to_string()];
+    header.extend(result.table_data_1.primary_key.iter().cloned());
+    for pair in pairs.iter() {
+        header.push(format!("{}__left", pair.column_1));
+        header.push(format!("{}__right", pair.column_1));
+    }
+    writer.write_record(&header)?;
+
+    for row in result.unique_table_1_rows.iter() {
+        writer.write_field("only_left")?;
+        for key in result.table_data_1.primary_key.iter() {
+            writer.write_field(options.field(row.get(key)))?;
+        }
+        for pair in pairs.iter() {
+            writer.write_field(options.field(row.get(&pair.column_1)))?;
+            writer.write_field(UNIFIED_MISSING_VALUE)?;
+        }
+        writer.write_record(None::<&[u8]>)?;
+    }
+
+    // the key columns of the second table are written under the names of the
+    // matching key columns of the first table
+    for row in result.unique_table_2_rows.iter() {
+        writer.write_field("only_right")?;
+        for key in result.table_data_2.primary_key.iter() {
+            writer.write_field(options.field(row.get(key)))?;
+        }
+        for pair in pairs.iter() {
+            writer.write_field(UNIFIED_MISSING_VALUE)?;
+            writer.write_field(options.field(row.get(&pair.column_2)))?;
+        }
+        writer.write_record(None::<&[u8]>)?;
+    }
+
+    for row in result.changed_rows.iter() {
+        writer.write_field("changed")?;
+        for (_, value) in row.key_values.iter() {
+            writer.write_field(options.field(Some(value)))?;
+        }
+        for pair in pairs.iter() {
+            match row.differences.iter().find(|difference| difference.column == pair.column_1) {
+                Some(difference) => {
+                    writer.write_field(options.field(Some(&difference.left_value)))?;
+                    writer.write_field(options.field(Some(&difference.right_value)))?;
+                }
+                None => {
+                    let value = options.field(row.unchanged_value(&pair.column_1));
+                    writer.write_field(&value)?;
+                    writer.write_field(&value)?;
+                }
+            }
+        }
+        writer.write_record(None::<&[u8]>)?;
+    }
+
+    writer.flush()?;
+    log.info(&format!("exported comparison data to {}", output_file_name));
+    Ok(())
 }
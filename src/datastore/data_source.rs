@@ -0,0 +1,50 @@
+use crate::{
+    interface::log::Log,
+    models::table_data::{
+        ColumnData,
+        TableData,
+    },
+};
+
+/// A backend that one side of the comparison can be read from.
+///
+/// Each implementation knows how to discover the schema of a table, which
+/// key the table should be joined on, and how to move the rows returned by a
+/// query into the sqlite staging table that the comparison runs against
+pub(crate) trait DataSource {
+    /// look up the list of columns for the given table
+    async fn get_columns(&self, table_name: &str, log: &Log) -> Vec<ColumnData>;
+
+    /// look up the list of key columns the given table should be joined on
+    async fn get_primary_key(&self, table_name: &str, log: &Log) -> Vec<String>;
+
+    /// schema and name of the table in the source, which is what statements
+    /// that change the source have to target. The schema is empty when there
+    /// is none
+    fn source_table(&self, table_name: &str) -> (String, String);
+
+    /// columns returned by the query, named and typed the way the source
+    /// reports them. None when the source doesn't run queries
+    async fn get_query_columns(&self, _query: &str, _log: &Log) -> Option<Vec<ColumnData>> {
+        None
+    }
+
+    /// run the query against the source and write the returned rows into a
+    /// sqlite table named after `table_data.table_name`
+    async fn load_rows(&self, query: &str, table_data: &TableData, log: &Log);
+
+    /// discover the columns and key of the table and wrap them up in a
+    /// `TableData` struct for the comparison. When key columns are passed in
+    /// they are used instead of discovering the key
+    async fn get_table_data(&self, table_name: &str, key_columns: &[String], log: &Log) -> TableData {
+        let columns = self.get_columns(table_name, log).await;
+        let primary_key = if key_columns.is_empty() {
+            self.get_primary_key(table_name, log).await
+        } else {
+            key_columns.to_vec()
+        };
+        let mut table_data = TableData::new(table_name.to_string(), columns, primary_key);
+        (table_data.source_schema, table_data.source_table) = self.source_table(table_name);
+        table_data
+    }
+}
@@ -4,21 +4,14 @@ use std::time::SystemTime;
 use crate::{
     datastore::{
         mysql::get_mysql_connection,
-        transformer::mysql_type_to_sqlite_type,
         generator
     },
     interface::{
         log::Log,
-        argument_parser
+        argument_parser,
+        argument_parser::SourceType,
     },
 };
-use sqlx::{
-    Pool,
-    mysql::MySqlRow,
-    Row,
-    Column,
-    TypeInfo
-};
 
 
 /// if args.generate_data is set then generate the data for the two tables
@@ -30,7 +23,8 @@ pub fn generate_data(args: &argument_parser::Arguments, log: &Log){
 
     log.debug("data creation underway");
     let mut now = SystemTime::now();
-    block_on(generator::create_new_mysql_data(args.number_of_rows_to_generate, &args.table_name_1, log));
+    let table_name_1 = mysql_table_name(&args.source_type_1, &args.table_name_1);
+    block_on(generator::create_new_mysql_data(args.number_of_rows_to_generate, table_name_1, &args.connection_url_1, log));
     match now.elapsed(){
         Ok(elapsed) => {
             // implement a profiling system to only measure if that flag is set
@@ -45,7 +39,8 @@ pub fn generate_data(args: &argument_parser::Arguments, log: &Log){
     log.debug("starting second data generation");
     now = SystemTime::now();
 
-    block_on(generator::create_new_mysql_data(args.number_of_rows_to_generate, &args.table_name_2, log));
+    let table_name_2 = mysql_table_name(&args.source_type_2, &args.table_name_2);
+    block_on(generator::create_new_mysql_data(args.number_of_rows_to_generate, table_name_2, &args.connection_url_2, log));
     match now.elapsed(){
         Ok(elapsed) => {
             let log_message = format!("Time it took to create 2nd table: {}.{}", elapsed.as_secs(),elapsed.subsec_millis());
@@ -57,9 +52,18 @@ pub fn generate_data(args: &argument_parser::Arguments, log: &Log){
     }
 }
 
+/// name of the mysql table the data of one side is generated into, which is
+/// the table passed in or the staging table name when none was
+fn mysql_table_name<'a>(source_type: &'a SourceType, table_name: &'a str) -> &'a str {
+    match source_type {
+        SourceType::MySqlTable(table_reference) => table_reference,
+        _ => table_name,
+    }
+}
+
 /// Create a new table in the mysql database and populate it with random data
-pub(crate) async fn create_new_mysql_data(num_rows_to_generate: i32, table_name: &str, log: &Log){
-    let pool = get_mysql_connection("test", log).await;
+pub(crate) async fn create_new_mysql_data(num_rows_to_generate: i32, table_name: &str, connection_url: &str, log: &Log){
+    let pool = get_mysql_connection(connection_url, log).await;
     let create_new_table_query = format!(
         "CREATE TABLE IF NOT EXISTS {}
         (
@@ -132,33 +136,3 @@ fn random_string(len: usize) -> String {
     }
     result
 }
-
-// generates a new sqlite table from a passed in mysql row
-pub async fn export_mysql_rows_to_sqlite_table(
-    mysql_rows: &[MySqlRow],
-    sqlite_pool: &Pool<sqlx::Sqlite>,
-    table_name: &str,
-) -> bool {
-    let mut create_query = format!("create table if not exists {} (", table_name);
-
-    // for each column in the first mysql row generate the column name and type
-    for column in mysql_rows[0].columns() {
-        create_query.push_str(column.name());
-        create_query.push(' ');
-        create_query.push_str(&mysql_type_to_sqlite_type(column.type_info().name()));
-        create_query.push(',');
-    }
-
-    // pop the last char off the string (,) and insert closing parens
-    create_query.pop();
-    create_query.push(')');
-
-    // execute and return the result
-    let result = sqlx::query(create_query.as_str()).execute(sqlite_pool).await;
-    match result {
-        Ok(_) => true,
-        Err(error) => {
-            panic!("error occurred while generating the new sqlite table: {:?}", error);
-        },
-    }
-}
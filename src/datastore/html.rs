@@ -0,0 +1,206 @@
+use crate::{
+    interface::log::Log,
+    models::{
+        comparison_data::ComparisonData,
+        result_row::ResultRow,
+        value::Value,
+    },
+};
+use std::fmt::Write as _;
+
+/// number of rows shown on each page of a table in the report
+const ROWS_PER_PAGE: usize = 50;
+
+/// styles inlined into the report so it can be opened without network access
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1, h2 { font-weight: normal; }
+table { border-collapse: collapse; margin-bottom: 0.5em; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }
+th { background: #f0f0f0; }
+td.changed { background: #fff6d5; }
+.old { background: #fdd; text-decoration: line-through; display: block; }
+.new { background: #dfd; display: block; }
+.null { color: #999; font-style: italic; }
+.summary td:last-child { text-align: right; }
+.pager button { margin: 0 4px; }
+";
+
+/// script inlined into the report that splits every paginated table into pages
+const SCRIPT: &str = "
+document.querySelectorAll('table.paginated').forEach(function (table) {
+    var rows = Array.prototype.slice.call(table.tBodies[0].rows);
+    var pageSize = parseInt(table.dataset.pageSize, 10);
+    var pageCount = Math.max(1, Math.ceil(rows.length / pageSize));
+    var pager = document.getElementById(table.id + '_pager');
+    var page = 0;
+    function show() {
+        rows.forEach(function (row, i) {
+            row.style.display = Math.floor(i / pageSize) === page ? '' : 'none';
+        });
+        pager.querySelector('span').textContent = 'page ' + (page + 1) + ' of ' + pageCount;
+    }
+    pager.querySelector('.previous').onclick = function () { if (page > 0) { page--; show(); } };
+    pager.querySelector('.next').onclick = function () { if (page < pageCount - 1) { page++; show(); } };
+    show();
+});
+";
+
+/// escape text so it can be placed inside html
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// render a value, greying out nulls so they can't be confused with the text NULL
+fn value_to_html(value: &Value) -> String {
+    match value.as_string() {
+        Some(text) => escape_html(&text),
+        None => "<span class=\"null\">NULL</span>".to_string(),
+    }
+}
+
+/// open a table that is split into pages by the inlined script
+fn open_paginated_table(html: &mut String, id: &str, headers: &[String]) {
+    let _ = write!(html, "<table class=\"paginated\" id=\"{}\" data-page-size=\"{}\"><thead><tr>", id, ROWS_PER_PAGE);
+    for header in headers {
+        let _ = write!(html, "<th>{}</th>", escape_html(header));
+    }
+    html.push_str("</tr></thead><tbody>\n");
+}
+
+/// close a paginated table and add the buttons to move between its pages
+fn close_paginated_table(html: &mut String, id: &str) {
+    html.push_str("</tbody></table>\n");
+    let _ = writeln!(
+        html,
+        "<div class=\"pager\" id=\"{}_pager\"><button class=\"previous\">previous</button><span></span><button class=\"next\">next</button></div>",
+        id
+    );
+}
+
+/// render the rows that only exist in one of the tables
+fn push_unique_rows(html: &mut String, id: &str, title: &str, rows: &[ResultRow]) {
+    let _ = writeln!(html, "<h2>{} ({})</h2>", escape_html(title), rows.len());
+    let Some(first_row) = rows.first() else {
+        return;
+    };
+
+    open_paginated_table(html, id, &first_row.columns);
+    for row in rows {
+        html.push_str("<tr>");
+        for value in row.values.iter() {
+            let _ = write!(html, "<td>{}</td>", value_to_html(value));
+        }
+        html.push_str("</tr>\n");
+    }
+    close_paginated_table(html, id);
+}
+
+/// build the whole report as a single html document, naming the tables after
+/// their sources rather than their staging tables
+pub fn comparison_data_to_html(result: &ComparisonData) -> String {
+    let table_1 = &result.table_data_1.source_name();
+    let table_2 = &result.table_data_2.source_name();
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{} vs {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape_html(table_1),
+        escape_html(table_2),
+        STYLE
+    );
+    let _ = writeln!(html, "<h1>{} vs {}</h1>", escape_html(table_1), escape_html(table_2));
+    let _ = writeln!(
+        html,
+        "<p>generated {} by data comparison tool {}</p>",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    // summary counts
+    html.push_str("<h2>Summary</h2>\n<table class=\"summary\">\n");
+    for (label, count) in [
+        (format!("rows only in {}", table_1), result.unique_table_1_rows.len()),
+        (format!("rows only in {}", table_2), result.unique_table_2_rows.len()),
+        ("changed rows".to_string(), result.changed_rows.len()),
+        ("unchanged rows".to_string(), result.unchanged_row_count),
+    ] {
+        let _ = writeln!(html, "<tr><td>{}</td><td>{}</td></tr>", escape_html(&label), count);
+    }
+    html.push_str("</table>\n");
+
+    // schema differences
+    html.push_str("<h2>Schema differences</h2>\n");
+    if result.schema_diff.is_empty() {
+        html.push_str("<p>none</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for difference in result.schema_diff.differences.iter() {
+            let _ = writeln!(html, "<li>{}</li>", escape_html(&difference.describe(table_1, table_2)));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    // changed rows, one column per compared column with the old and new value
+    // shown in the cells that differ
+    let _ = writeln!(html, "<h2>Changed rows ({})</h2>", result.changed_rows.len());
+    if let Some(first_row) = result.changed_rows.first() {
+        let mut headers: Vec<String> = first_row.key_values.iter().map(|(key, _)| key.clone()).collect();
+        headers.extend(result.column_mapping.pairs.iter().map(|pair| pair.column_1.clone()));
+        open_paginated_table(&mut html, "changed_rows", &headers);
+
+        for row in result.changed_rows.iter() {
+            html.push_str("<tr>");
+            for (_, value) in row.key_values.iter() {
+                let _ = write!(html, "<td>{}</td>", value_to_html(value));
+            }
+            for pair in result.column_mapping.pairs.iter() {
+                match row.differences.iter().find(|difference| difference.column == pair.column_1) {
+                    Some(difference) => {
+                        let _ = write!(
+                            html,
+                            "<td class=\"changed\"><span class=\"old\">{}</span><span class=\"new\">{}</span></td>",
+                            value_to_html(&difference.left_value),
+                            value_to_html(&difference.right_value)
+                        );
+                    }
+                    None => html.push_str("<td></td>"),
+                }
+            }
+            html.push_str("</tr>\n");
+        }
+        close_paginated_table(&mut html, "changed_rows");
+    }
+
+    push_unique_rows(&mut html, "only_left_rows", &format!("Rows only in {}", table_1), &result.unique_table_1_rows);
+    push_unique_rows(&mut html, "only_right_rows", &format!("Rows only in {}", table_2), &result.unique_table_2_rows);
+
+    let _ = write!(html, "<script>{}</script>\n</body>\n</html>\n", SCRIPT);
+    html
+}
+
+/// write the comparison data out as a single html page that can be opened
+/// offline, with the summary counts, schema differences and paginated tables
+/// of the changed and unique rows
+pub fn export_comparison_data_to_html(result: &ComparisonData, output_file_name: &str, log: &Log) {
+    match std::fs::write(output_file_name, comparison_data_to_html(result)) {
+        Ok(_) => {
+            log.info(&format!("exported comparison data to {}", output_file_name));
+        }
+        Err(error) => {
+            panic!("unable to write html output file {}: {}", output_file_name, error);
+        }
+    }
+}
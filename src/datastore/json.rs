@@ -0,0 +1,115 @@
+use crate::{
+    interface::log::Log,
+    models::{
+        changed_row::ChangedRow,
+        column_mapping::ColumnMapping,
+        comparison_data::ComparisonData,
+        database_comparison::DatabaseComparison,
+        result_row::ResultRow,
+        schema_diff::SchemaDifference,
+        table_data::TableData,
+    },
+};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::BufWriter,
+};
+
+/// Information about the comparison run written at the top of the report
+#[derive(Serialize)]
+struct RunMetadata<'a> {
+    tool_version: &'a str,
+    generated_at: String,
+    /// name of each table in its source rather than its staging table
+    table_1: String,
+    table_2: String,
+    only_left_count: usize,
+    only_right_count: usize,
+    changed_count: usize,
+    unchanged_count: usize,
+}
+
+/// The columns and keys of both tables, how their columns were paired up and
+/// how the two schemas differ
+#[derive(Serialize)]
+struct Schema<'a> {
+    table_1: &'a TableData,
+    table_2: &'a TableData,
+    column_mapping: &'a ColumnMapping,
+    differences: &'a [SchemaDifference],
+}
+
+/// Layout of the json document written by the exporter
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    metadata: RunMetadata<'a>,
+    schema: Schema<'a>,
+    only_left: &'a [ResultRow],
+    only_right: &'a [ResultRow],
+    changed: &'a [ChangedRow],
+}
+
+/// write the comparison data out as a single json document containing the run
+/// metadata, the schema of both tables, the rows unique to each table and the
+/// changed rows with the before and after value of every differing column
+pub fn export_comparison_data_to_json(result: &ComparisonData, output_file_name: &str, log: &Log) {
+    let report = JsonReport {
+        metadata: RunMetadata {
+            tool_version: env!("CARGO_PKG_VERSION"),
+            generated_at: chrono::Local::now().to_rfc3339(),
+            table_1: result.table_data_1.source_name(),
+            table_2: result.table_data_2.source_name(),
+            only_left_count: result.unique_table_1_rows.len(),
+            only_right_count: result.unique_table_2_rows.len(),
+            changed_count: result.changed_rows.len(),
+            unchanged_count: result.unchanged_row_count,
+        },
+        schema: Schema {
+            table_1: &result.table_data_1,
+            table_2: &result.table_data_2,
+            column_mapping: &result.column_mapping,
+            differences: &result.schema_diff.differences,
+        },
+        only_left: &result.unique_table_1_rows,
+        only_right: &result.unique_table_2_rows,
+        changed: &result.changed_rows,
+    };
+
+    let file = match File::create(output_file_name) {
+        Ok(file) => file,
+        Err(error) => {
+            panic!("unable to create json output file {}: {}", output_file_name, error);
+        }
+    };
+
+    match serde_json::to_writer_pretty(BufWriter::new(file), &report) {
+        Ok(_) => {
+            log.info(&format!("exported comparison data to {}", output_file_name));
+        }
+        Err(error) => {
+            panic!("error occurred while writing json output: {}", error);
+        }
+    }
+}
+
+/// write the database level report of a whole database comparison, listing
+/// the tables found in only one of the databases and the row counts of every
+/// pair of tables that was compared
+pub fn export_database_comparison_to_json(report: &DatabaseComparison, output_file_name: &str, log: &Log) {
+    let file = match File::create(output_file_name) {
+        Ok(file) => file,
+        Err(error) => {
+            panic!("unable to create json output file {}: {}", output_file_name, error);
+        }
+    };
+
+    match serde_json::to_writer_pretty(BufWriter::new(file), report) {
+        Ok(_) => {
+            log.info(&format!("exported database report to {}", output_file_name));
+        }
+        Err(error) => {
+            panic!("error occurred while writing json output: {}", error);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    interface::log::Log,
+    models::comparison_data::ComparisonData,
+};
+
+/// number of changed row keys listed in the details of a failing test case
+const CHANGED_KEYS_TO_LIST: usize = 10;
+
+/// escape text so it can be placed inside of an xml attribute or element
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// one line summary of the differences between the two tables
+fn difference_summary(result: &ComparisonData) -> String {
+    let mut summary = format!(
+        "{} rows only in {}, {} rows only in {}, {} changed rows",
+        result.unique_table_1_row_count(),
+        result.table_data_1.source_name(),
+        result.unique_table_2_row_count(),
+        result.table_data_2.source_name(),
+        result.changed_row_count()
+    );
+    if !result.schema_diff.is_empty() {
+        summary.push_str(&format!(", {} schema differences", result.schema_diff.differences.len()));
+    }
+    summary
+}
+
+/// A comparison reported as a junit test case
+pub struct JunitTestCase<'a> {
+    /// name of the job or table the comparison was run for
+    pub name: String,
+    /// result of the comparison, or the error it failed with
+    pub outcome: Result<&'a ComparisonData, &'a str>,
+}
+
+impl<'a> JunitTestCase<'a> {
+    /// test case named after the tables in their sources rather than their
+    /// staging tables, so it stays the same across runs
+    pub fn from_result(result: &'a ComparisonData) -> JunitTestCase<'a> {
+        JunitTestCase {
+            name: format!("{} vs {}", result.table_data_1.source_name(), result.table_data_2.source_name()),
+            outcome: Ok(result),
+        }
+    }
+}
+
+/// build a junit report where every comparison is a test case that fails
+/// when the two tables differ and errors when the comparison couldn't be run
+pub fn comparison_results_to_junit(test_cases: &[JunitTestCase]) -> String {
+    let failures = test_cases
+        .iter()
+        .filter(|test_case| matches!(test_case.outcome, Ok(result) if result.has_differences()))
+        .count();
+    let errors = test_cases.iter().filter(|test_case| test_case.outcome.is_err()).count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"data_comparison_tool\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+        test_cases.len(),
+        failures,
+        errors
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"data comparison\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"0\">\n",
+        test_cases.len(),
+        failures,
+        errors
+    ));
+
+    for test_case in test_cases.iter() {
+        xml.push_str(&format!(
+            "    <testcase classname=\"data_comparison_tool\" name=\"{}\"",
+            escape_xml(&test_case.name)
+        ));
+        let result = match test_case.outcome {
+            Ok(result) => result,
+            Err(message) => {
+                xml.push_str(">\n");
+                xml.push_str(&format!(
+                    "      <error type=\"error\" message=\"{}\">{}</error>\n",
+                    escape_xml(message),
+                    escape_xml(message)
+                ));
+                xml.push_str("    </testcase>\n");
+                continue;
+            }
+        };
+        let table_1 = result.table_data_1.source_name();
+        let table_2 = result.table_data_2.source_name();
+        if !result.has_differences() {
+            xml.push_str(" />\n");
+            continue;
+        }
+
+        // list the schema differences and the first few changed keys so the
+        // failure can be looked into without opening the full output
+        let mut details = vec![difference_summary(result)];
+        for difference in result.schema_diff.differences.iter() {
+            details.push(difference.describe(&table_1, &table_2));
+        }
+        for row in result.changed_rows.iter().take(CHANGED_KEYS_TO_LIST) {
+            let columns: Vec<&str> = row.differences.iter().map(|difference| difference.column.as_str()).collect();
+            details.push(format!("changed {}: {}", row.key_string(), columns.join(", ")));
+        }
+
+        xml.push_str(">\n");
+        xml.push_str(&format!(
+            "      <failure type=\"differences\" message=\"{}\">{}</failure>\n",
+            escape_xml(&difference_summary(result)),
+            escape_xml(&details.join("\n"))
+        ));
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// write a junit xml report of the comparisons so ci systems can show the
+/// tables that differ as failing tests and the ones that couldn't be compared
+/// as errors
+pub fn export_comparison_results_to_junit(test_cases: &[JunitTestCase], output_file_name: &str, log: &Log) {
+    match std::fs::write(output_file_name, comparison_results_to_junit(test_cases)) {
+        Ok(_) => {
+            log.info(&format!("exported junit report to {}", output_file_name));
+        }
+        Err(error) => {
+            panic!("unable to write junit report {}: {}", output_file_name, error);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    interface::log::Log,
+    models::{
+        diff_record::{
+            DiffRecord,
+            StreamedCounts,
+        },
+        schema_diff::SchemaDifference,
+    },
+};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{
+        BufWriter,
+        Write,
+    },
+};
+
+/// A schema difference written as a line of its own, tagged like the row
+/// records so readers can tell the lines apart by their type
+#[derive(Serialize)]
+struct SchemaRecord<'a> {
+    #[serde(rename = "type")]
+    record_type: &'static str,
+    #[serde(flatten)]
+    difference: &'a SchemaDifference,
+}
+
+/// Writes comparison differences to a newline delimited json file, one record
+/// per line, as they are read out of the comparison engine
+pub struct NdjsonWriter {
+    writer: BufWriter<File>,
+    output_file_name: String,
+    counts: StreamedCounts,
+}
+
+impl NdjsonWriter {
+    pub fn new(output_file_name: &str) -> NdjsonWriter {
+        let file = match File::create(output_file_name) {
+            Ok(file) => file,
+            Err(error) => {
+                panic!("unable to create ndjson output file {}: {}", output_file_name, error);
+            }
+        };
+
+        NdjsonWriter {
+            writer: BufWriter::new(file),
+            output_file_name: output_file_name.to_string(),
+            counts: StreamedCounts::default(),
+        }
+    }
+
+    /// write a single record as one line of json
+    pub fn write_record(&mut self, record: &DiffRecord) {
+        self.write_line(record);
+        self.counts.add(record);
+    }
+
+    /// write the differences between the schemas of the tables, one line
+    /// each, ahead of the rows
+    pub fn write_schema_differences(&mut self, differences: &[SchemaDifference]) {
+        for difference in differences {
+            self.write_line(&SchemaRecord {
+                record_type: "schema_difference",
+                difference,
+            });
+        }
+    }
+
+    fn write_line<T: Serialize>(&mut self, record: &T) {
+        if let Err(error) = serde_json::to_writer(&mut self.writer, record) {
+            panic!("error occurred while writing ndjson output: {}", error);
+        }
+        if let Err(error) = self.writer.write_all(b"\n") {
+            panic!("error occurred while writing ndjson output: {}", error);
+        }
+    }
+
+    /// flush whatever is left in the buffer and log how many records of each
+    /// type were written, returning those counts
+    pub fn finish(mut self, log: &Log) -> StreamedCounts {
+        if let Err(error) = self.writer.flush() {
+            panic!("error occurred while writing ndjson output: {}", error);
+        }
+        log.info(&format!("rows in table 1 that are not in table 2: {}", self.counts.only_left));
+        log.info(&format!("rows in table 2 that are not in table 1: {}", self.counts.only_right));
+        log.info(&format!("rows that are different between the two tables: {}", self.counts.changed));
+        log.info(&format!("exported comparison data to {}", self.output_file_name));
+        self.counts
+    }
+}
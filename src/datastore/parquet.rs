@@ -0,0 +1,206 @@
+use crate::{
+    interface::log::Log,
+    models::{
+        comparison_data::ComparisonData,
+        value::Value,
+    },
+};
+use arrow_array::{
+    ArrayRef,
+    BinaryArray,
+    Date32Array,
+    Float64Array,
+    Int64Array,
+    RecordBatch,
+    StringArray,
+    Time64MicrosecondArray,
+    TimestampMicrosecondArray,
+};
+use arrow_schema::{
+    DataType,
+    Field,
+    Schema,
+    TimeUnit,
+};
+use chrono::{
+    NaiveDate,
+    Timelike,
+};
+use parquet::{
+    arrow::ArrowWriter,
+    errors::ParquetError,
+};
+use std::{
+    fs::File,
+    sync::Arc,
+};
+
+/// number of rows written to the parquet file at a time
+const ROWS_PER_BATCH: usize = 8192;
+
+/// A column of the parquet output with its value in every row, None when the
+/// row has no value for the column
+struct OutputColumn<'a> {
+    name: String,
+    values: Vec<Option<&'a Value>>,
+}
+
+impl<'a> OutputColumn<'a> {
+    fn new(name: String) -> OutputColumn<'a> {
+        OutputColumn {
+            name,
+            values: Vec::new(),
+        }
+    }
+}
+
+/// pick the parquet type of a column from the values in it. Integers mixed with
+/// floats are widened to floats and any other mix falls back to text
+fn column_data_type(values: &[Option<&Value>]) -> DataType {
+    let mut data_type = None;
+    for value in values.iter().flatten() {
+        let value_type = match value {
+            Value::Null => continue,
+            Value::Int(_) => DataType::Int64,
+            Value::Float(_) => DataType::Float64,
+            Value::Bytes(_) => DataType::Binary,
+            Value::Date(_) => DataType::Date32,
+            Value::Time(_) => DataType::Time64(TimeUnit::Microsecond),
+            Value::DateTime(_) => DataType::Timestamp(TimeUnit::Microsecond, None),
+            Value::Text(_) | Value::Decimal(_) => DataType::Utf8,
+        };
+
+        data_type = match data_type {
+            None => Some(value_type),
+            Some(current) if current == value_type => Some(current),
+            Some(DataType::Int64 | DataType::Float64)
+                if matches!(value_type, DataType::Int64 | DataType::Float64) =>
+            {
+                Some(DataType::Float64)
+            }
+            Some(_) => return DataType::Utf8,
+        };
+    }
+    data_type.unwrap_or(DataType::Utf8)
+}
+
+/// build an arrow array of the given type out of a slice of a column
+fn build_array(values: &[Option<&Value>], data_type: &DataType) -> ArrayRef {
+    match data_type {
+        DataType::Int64 => Arc::new(Int64Array::from_iter(values.iter().map(|value| match value {
+            Some(Value::Int(value)) => Some(*value),
+            _ => None,
+        }))),
+        DataType::Float64 => Arc::new(Float64Array::from_iter(values.iter().map(|value| match value {
+            Some(Value::Int(value)) => Some(*value as f64),
+            Some(Value::Float(value)) => Some(*value),
+            _ => None,
+        }))),
+        DataType::Binary => Arc::new(BinaryArray::from_iter(values.iter().map(|value| match value {
+            Some(Value::Bytes(value)) => Some(value.as_slice()),
+            _ => None,
+        }))),
+        DataType::Date32 => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+            Arc::new(Date32Array::from_iter(values.iter().map(|value| match value {
+                Some(Value::Date(value)) => Some(value.signed_duration_since(epoch).num_days() as i32),
+                _ => None,
+            })))
+        }
+        DataType::Time64(_) => Arc::new(Time64MicrosecondArray::from_iter(values.iter().map(|value| match value {
+            Some(Value::Time(value)) => {
+                Some(value.num_seconds_from_midnight() as i64 * 1_000_000 + value.nanosecond() as i64 / 1_000)
+            }
+            _ => None,
+        }))),
+        DataType::Timestamp(_, _) => Arc::new(TimestampMicrosecondArray::from_iter(values.iter().map(|value| match value {
+            Some(Value::DateTime(value)) => Some(value.and_utc().timestamp_micros()),
+            _ => None,
+        }))),
+        _ => Arc::new(StringArray::from_iter(values.iter().map(|value| value.and_then(Value::as_string)))),
+    }
+}
+
+/// write every difference to a parquet file laid out like the unified csv: a
+/// `_change` column (only_left, only_right or changed), the key columns and a
+/// `column__left`/`column__right` pair for every compared column. Column
+/// types are kept from the values so integers, floats, dates and timestamps
+/// don't pass through text
+pub fn export_comparison_data_to_parquet(
+    result: &ComparisonData,
+    output_file_name: &str,
+    log: &Log,
+) -> Result<(), ParquetError> {
+    let pairs = &result.column_mapping.pairs;
+    let key_count = result.table_data_1.primary_key.len();
+    let mut change_types: Vec<&str> = Vec::new();
+    let mut columns: Vec<OutputColumn> = result
+        .table_data_1
+        .primary_key
+        .iter()
+        .map(|key| OutputColumn::new(key.clone()))
+        .collect();
+    for pair in pairs.iter() {
+        columns.push(OutputColumn::new(format!("{}__left", pair.column_1)));
+        columns.push(OutputColumn::new(format!("{}__right", pair.column_1)));
+    }
+
+    for row in result.unique_table_1_rows.iter() {
+        change_types.push("only_left");
+        for (i, key) in result.table_data_1.primary_key.iter().enumerate() {
+            columns[i].values.push(row.get(key));
+        }
+        for (i, pair) in pairs.iter().enumerate() {
+            columns[key_count + i * 2].values.push(row.get(&pair.column_1));
+            columns[key_count + i * 2 + 1].values.push(None);
+        }
+    }
+
+    // the key columns of the second table are written under the names of the
+    // matching key columns of the first table
+    for row in result.unique_table_2_rows.iter() {
+        change_types.push("only_right");
+        for (i, key) in result.table_data_2.primary_key.iter().enumerate().take(key_count) {
+            columns[i].values.push(row.get(key));
+        }
+        for (i, pair) in pairs.iter().enumerate() {
+            columns[key_count + i * 2].values.push(None);
+            columns[key_count + i * 2 + 1].values.push(row.get(&pair.column_2));
+        }
+    }
+
+    // only the columns that differ are filled in for changed rows
+    for row in result.changed_rows.iter() {
+        change_types.push("changed");
+        for (i, (_, value)) in row.key_values.iter().enumerate().take(key_count) {
+            columns[i].values.push(Some(value));
+        }
+        for (i, pair) in pairs.iter().enumerate() {
+            let difference = row.differences.iter().find(|difference| difference.column == pair.column_1);
+            columns[key_count + i * 2].values.push(difference.map(|difference| &difference.left_value));
+            columns[key_count + i * 2 + 1].values.push(difference.map(|difference| &difference.right_value));
+        }
+    }
+
+    let data_types: Vec<DataType> = columns.iter().map(|column| column_data_type(&column.values)).collect();
+    let mut fields = vec![Field::new("_change", DataType::Utf8, false)];
+    for (column, data_type) in columns.iter().zip(data_types.iter()) {
+        fields.push(Field::new(&column.name, data_type.clone(), true));
+    }
+    let schema = Arc::new(Schema::new(fields));
+
+    let file = File::create(output_file_name)?;
+    let mut writer = ArrowWriter::try_new(file, schema.clone(), None)?;
+    for start in (0..change_types.len()).step_by(ROWS_PER_BATCH) {
+        let end = (start + ROWS_PER_BATCH).min(change_types.len());
+        let mut arrays: Vec<ArrayRef> = vec![Arc::new(StringArray::from(change_types[start..end].to_vec()))];
+        for (column, data_type) in columns.iter().zip(data_types.iter()) {
+            arrays.push(build_array(&column.values[start..end], data_type));
+        }
+        writer.write(&RecordBatch::try_new(schema.clone(), arrays)?)?;
+    }
+    writer.close()?;
+
+    log.info(&format!("exported {} rows of comparison data to {}", change_types.len(), output_file_name));
+    Ok(())
+}
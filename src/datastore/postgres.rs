@@ -0,0 +1,286 @@
+use crate::{
+    interface::log::Log,
+    models::table_data::{
+        ColumnData,
+        TableData,
+    },
+    datastore::{
+        data_source::DataSource,
+        sqlite,
+        transformer::postgres_type_to_sqlite_type,
+    },
+};
+use sqlx::{
+    Column,
+    Executor,
+    Row,
+    Pool,
+    Statement,
+    TypeInfo,
+    postgres::{
+        PgPoolOptions,
+        Postgres,
+    }
+};
+use std::env;
+
+/// Data source that reads a table out of a postgres database
+pub(crate) struct PostgresSource {
+    /// url of the database to connect to, empty to use the
+    /// POSTGRES_CONNECTION_STRING env var
+    pub connection_url: String,
+    /// schema the table lives in
+    pub schema_name: String,
+    /// name of the table inside of the schema
+    pub table_name: String,
+}
+
+impl PostgresSource {
+    /// create a new source from a `schema.table` or `table` reference, tables
+    /// without a schema are looked up in the public schema
+    pub fn new(connection_url: &str, table_reference: &str) -> PostgresSource {
+        match table_reference.split_once('.') {
+            Some((schema_name, table_name)) => PostgresSource {
+                connection_url: connection_url.to_string(),
+                schema_name: schema_name.to_string(),
+                table_name: table_name.to_string(),
+            },
+            None => PostgresSource {
+                connection_url: connection_url.to_string(),
+                schema_name: "public".to_string(),
+                table_name: table_reference.to_string(),
+            },
+        }
+    }
+}
+
+impl DataSource for PostgresSource {
+    /// look the columns, their types, nullability and lengths up in
+    /// information_schema
+    async fn get_columns(&self, _table_name: &str, log: &Log) -> Vec<ColumnData> {
+        let pool = get_postgres_connection(&self.connection_url, log).await;
+        let result = sqlx::query(
+            "select column_name::text, data_type::text, is_nullable = 'YES', character_maximum_length::bigint
+            from information_schema.columns
+            where table_schema = $1 and table_name = $2
+            order by ordinal_position",
+        )
+        .bind(&self.schema_name)
+        .bind(&self.table_name)
+        .fetch_all(&pool)
+        .await;
+
+        match result {
+            Ok(rows) => {
+                if rows.is_empty() {
+                    panic!("table {}.{} not found in postgres", self.schema_name, self.table_name);
+                }
+                rows.iter()
+                    .map(|row| ColumnData {
+                        nullable: Some(row.get(2)),
+                        max_length: row.get(3),
+                        ..ColumnData::new(row.get(0), row.get(1))
+                    })
+                    .collect()
+            },
+            Err(error) => {
+                panic!("error occurred while fetching table data from {:?}", error);
+            },
+        }
+    }
+
+    /// look the primary key constraint of the table up in information_schema,
+    /// falling back to the columns of the first unique index on the table when
+    /// there is no primary key
+    async fn get_primary_key(&self, _table_name: &str, log: &Log) -> Vec<String> {
+        let pool = get_postgres_connection(&self.connection_url, log).await;
+        let primary_key_result = sqlx::query(
+            "select kcu.column_name::text
+            from information_schema.table_constraints tc
+            join information_schema.key_column_usage kcu
+                on tc.constraint_name = kcu.constraint_name
+                and tc.table_schema = kcu.table_schema
+                and tc.table_name = kcu.table_name
+            where tc.constraint_type = 'PRIMARY KEY'
+                and tc.table_schema = $1
+                and tc.table_name = $2
+            order by kcu.ordinal_position",
+        )
+        .bind(&self.schema_name)
+        .bind(&self.table_name)
+        .fetch_all(&pool)
+        .await;
+
+        match primary_key_result {
+            Ok(rows) => {
+                if !rows.is_empty() {
+                    return rows.iter().map(|row| row.get(0)).collect();
+                }
+            },
+            Err(error) => {
+                panic!("error occurred while fetching primary key from {:?}", error);
+            },
+        }
+
+        log.warn(&format!(
+            "no primary key found for {}.{}, looking for a unique index",
+            self.schema_name, self.table_name
+        ));
+        // partial indexes and indexes on expressions don't identify a row
+        let unique_index_result = sqlx::query(
+            "select index_class.relname::text, a.attname::text
+            from pg_index i
+            join pg_class table_class on table_class.oid = i.indrelid
+            join pg_namespace n on n.oid = table_class.relnamespace
+            join pg_class index_class on index_class.oid = i.indexrelid
+            join pg_attribute a on a.attrelid = table_class.oid and a.attnum = any(i.indkey)
+            where n.nspname = $1
+                and table_class.relname = $2
+                and i.indisunique
+                and i.indpred is null
+                and i.indexprs is null
+            order by index_class.relname, array_position(i.indkey::int2[], a.attnum)",
+        )
+        .bind(&self.schema_name)
+        .bind(&self.table_name)
+        .fetch_all(&pool)
+        .await;
+
+        match unique_index_result {
+            Ok(rows) => match rows.first() {
+                Some(first_row) => {
+                    // only take the columns that belong to the first index
+                    let index_name: String = first_row.get(0);
+                    rows.iter()
+                        .filter(|row| row.get::<String, _>(0) == index_name)
+                        .map(|row| row.get(1))
+                        .collect()
+                },
+                None => {
+                    panic!(
+                        "table {}.{} has no primary key or unique index to join on",
+                        self.schema_name, self.table_name
+                    );
+                },
+            },
+            Err(error) => {
+                panic!("error occurred while fetching unique indexes from {:?}", error);
+            },
+        }
+    }
+
+    fn source_table(&self, _table_name: &str) -> (String, String) {
+        (self.schema_name.clone(), self.table_name.clone())
+    }
+
+    /// the columns are read from the prepared query, with their types named
+    /// the way pg_type names them, like int4 or bool
+    async fn get_query_columns(&self, query: &str, log: &Log) -> Option<Vec<ColumnData>> {
+        let pool = get_postgres_connection(&self.connection_url, log).await;
+        match pool.prepare(query).await {
+            Ok(statement) => Some(
+                statement
+                    .columns()
+                    .iter()
+                    .map(|column| ColumnData::new(column.name().to_string(), column.type_info().name().to_lowercase()))
+                    .collect()
+            ),
+            Err(error) => {
+                panic!("error occurred while preparing the query {}: {:?}", query, error);
+            },
+        }
+    }
+
+    /// every column is selected as text so that types sqlx can't decode
+    /// (numeric, uuid, json...) survive the trip, and then parsed back into
+    /// the mapped sqlite type on insert
+    async fn load_rows(&self, query: &str, table_data: &TableData, log: &Log) {
+        let query = if query.is_empty() {
+            format!("select * from \"{}\".\"{}\"", self.schema_name, self.table_name)
+        } else {
+            query.to_string()
+        };
+
+        // the columns are read off of the query itself, so a query that selects
+        // some of the columns or renames them is staged with the columns it
+        // returns. Every column is cast to text on the way out
+        let query_columns = self.get_query_columns(&query, log).await.unwrap_or_default();
+        let select_columns: Vec<String> = query_columns
+            .iter()
+            .map(|column| {
+                let quoted_name = format!("\"{}\"", column.name.replace('"', "\"\""));
+                format!("{}::text as {}", quoted_name, quoted_name)
+            })
+            .collect();
+        let text_query = format!("select {} from ({}) as source_query", select_columns.join(","), query);
+
+        let pool = get_postgres_connection(&self.connection_url, log).await;
+        let rows = match sqlx::query(&text_query).fetch_all(&pool).await {
+            Ok(rows) => rows,
+            Err(error) => {
+                panic!("error: {:?}", error);
+            },
+        };
+
+        // booleans come back as true/false and are stored as 1/0 in sqlite
+        let values: Vec<Vec<Option<String>>> = rows
+            .iter()
+            .map(|row| {
+                query_columns.iter().enumerate().map(|(i, column)| {
+                    let value: Option<String> = row.get(i);
+                    match (column.data_type.as_str(), value.as_deref()) {
+                        ("bool", Some("true")) => Some("1".to_string()),
+                        ("bool", Some("false")) => Some("0".to_string()),
+                        _ => value,
+                    }
+                })
+                .collect()
+            })
+            .collect();
+
+        // map the postgres types onto the sqlite staging table
+        let sqlite_columns: Vec<ColumnData> = query_columns
+            .iter()
+            .map(|column| ColumnData::new(column.name.clone(), postgres_type_to_sqlite_type(&column.data_type)))
+            .collect();
+
+        let sqlite_pool = sqlite::get_connection(&table_data.sqlite_file_name, log).await;
+        sqlite::create_staging_table(&table_data.table_name, &sqlite_columns, &sqlite_pool).await;
+        log.info(&format!("created new sqlite table: {}", &table_data.table_name));
+        sqlite::insert_staging_rows(&table_data.table_name, &sqlite_columns, &values, &sqlite_pool).await;
+        log.info(&format!("loaded {} rows from {}.{}", values.len(), self.schema_name, self.table_name));
+    }
+}
+
+/// open a connection pool to the postgres database at the url passed in,
+/// falling back to the POSTGRES_CONNECTION_STRING env var when the url is empty
+pub(crate) async fn get_postgres_connection(connection_url: &str, log: &Log) -> Pool<Postgres> {
+    let postgres_connection_string = if connection_url.is_empty() {
+        match env::var("POSTGRES_CONNECTION_STRING") {
+            Ok(connection_string) => connection_string,
+            Err(_) => {
+                panic!(
+                    "no postgres connection url, pass -url1/-url2, set DCT_LEFT_URL/DCT_RIGHT_URL or set POSTGRES_CONNECTION_STRING"
+                );
+            },
+        }
+    } else {
+        connection_url.to_string()
+    };
+
+    // attempt to connect and handle success/fail accordingly
+    let result = PgPoolOptions::new()
+        .acquire_timeout(std::time::Duration::from_secs(5))
+        .connect(&postgres_connection_string)
+        .await;
+
+    match result {
+        Ok(pool) => {
+            log.info("connected to postgres database");
+            pool
+        },
+        Err(error) => {
+            panic!("unable to connect to postgres db {}", error);
+        },
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{
+    interface::{
+        argument_parser::SqlDialect,
+        log::Log,
+    },
+    models::{
+        comparison_data::ComparisonData,
+        table_data::TableData,
+        value::Value,
+    },
+};
+use std::{
+    fs::File,
+    io::{
+        BufWriter,
+        Write,
+    },
+};
+
+/// What a patch statement does to the second table
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchKind {
+    Insert,
+    Delete,
+    Update,
+}
+
+/// A single statement of the patch that makes the second table match the first
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatchStatement {
+    pub kind: PatchKind,
+    pub sql: String,
+}
+
+/// quote a column or table name so it is safe to use in the given dialect
+pub fn quote_identifier(name: &str, dialect: &SqlDialect) -> String {
+    match dialect {
+        SqlDialect::MySql => format!("`{}`", name.replace('`', "``")),
+        SqlDialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// format a value as a sql literal, escaping text for the given dialect
+pub fn value_to_sql_literal(value: &Value, dialect: &SqlDialect) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Int(value) => value.to_string(),
+        Value::Float(value) if value.is_finite() => value.to_string(),
+        Value::Float(_) => "NULL".to_string(),
+        Value::Bytes(value) => {
+            let hex: String = value.iter().map(|byte| format!("{:02X}", byte)).collect();
+            format!("X'{}'", hex)
+        }
+        // decimals are written unquoted as long as they actually look like a
+        // number
+        Value::Decimal(value)
+            if !value.is_empty()
+                && value.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')) =>
+        {
+            value.clone()
+        }
+        value => {
+            let text = value.to_string();
+            let escaped = match dialect {
+                // mysql treats backslashes as escape characters inside of strings
+                SqlDialect::MySql => text.replace('\\', "\\\\").replace('\'', "''"),
+                SqlDialect::Sqlite => text.replace('\'', "''"),
+            };
+            format!("'{}'", escaped)
+        }
+    }
+}
+
+/// quoted name of the table in its source rather than the name it was staged
+/// under, with its schema when it has one
+fn target_table_name(table_data: &TableData, dialect: &SqlDialect) -> String {
+    if table_data.source_schema.is_empty() {
+        quote_identifier(&table_data.source_table, dialect)
+    } else {
+        format!(
+            "{}.{}",
+            quote_identifier(&table_data.source_schema, dialect),
+            quote_identifier(&table_data.source_table, dialect)
+        )
+    }
+}
+
+/// find the column in the second table that a column of the first table is
+/// compared against. Key columns are paired up by position and the rest come
+/// from the column mapping
+fn table_2_column<'a>(result: &'a ComparisonData, column_1: &str) -> Option<&'a str> {
+    if let Some(position) = result.table_data_1.primary_key.iter().position(|key| key == column_1) {
+        return result.table_data_2.primary_key.get(position).map(String::as_str);
+    }
+    result
+        .column_mapping
+        .pairs
+        .iter()
+        .find(|pair| pair.column_1 == column_1)
+        .map(|pair| pair.column_2.as_str())
+}
+
+/// build the where clause that matches a single row of the second table on
+/// its key columns
+fn key_condition<'a>(columns: impl Iterator<Item = (&'a str, &'a Value)>, dialect: &SqlDialect) -> String {
+    columns
+        .map(|(column, value)| {
+            format!("{} = {}", quote_identifier(column, dialect), value_to_sql_literal(value, dialect))
+        })
+        .collect::<Vec<String>>()
+        .join(" AND ")
+}
+
+/// generate the statements that make the second table match the first:
+/// inserts for rows only in the first table, deletes for rows only in the
+/// second table and updates of just the differing columns for changed rows
+pub fn patch_statements(result: &ComparisonData, dialect: &SqlDialect) -> Vec<PatchStatement> {
+    let table_name = target_table_name(&result.table_data_2, dialect);
+    let mut statements = Vec::new();
+
+    for row in result.unique_table_1_rows.iter() {
+        // columns without a partner in the second table are left out
+        let (columns, values): (Vec<String>, Vec<String>) = row
+            .columns
+            .iter()
+            .zip(row.values.iter())
+            .filter_map(|(column, value)| {
+                table_2_column(result, column).map(|column_2| {
+                    (quote_identifier(column_2, dialect), value_to_sql_literal(value, dialect))
+                })
+            })
+            .unzip();
+        statements.push(PatchStatement {
+            kind: PatchKind::Insert,
+            sql: format!(
+                "INSERT INTO {} ({}) VALUES ({});",
+                table_name,
+                columns.join(", "),
+                values.join(", ")
+            ),
+        });
+    }
+
+    for row in result.unique_table_2_rows.iter() {
+        let keys = result
+            .table_data_2
+            .primary_key
+            .iter()
+            .map(|key| (key.as_str(), row.get(key).unwrap_or(&Value::Null)));
+        statements.push(PatchStatement {
+            kind: PatchKind::Delete,
+            sql: format!("DELETE FROM {} WHERE {};", table_name, key_condition(keys, dialect)),
+        });
+    }
+
+    for row in result.changed_rows.iter() {
+        let assignments: Vec<String> = row
+            .differences
+            .iter()
+            .filter_map(|difference| {
+                table_2_column(result, &difference.column).map(|column_2| {
+                    format!(
+                        "{} = {}",
+                        quote_identifier(column_2, dialect),
+                        value_to_sql_literal(&difference.left_value, dialect)
+                    )
+                })
+            })
+            .collect();
+        if assignments.is_empty() {
+            continue;
+        }
+
+        let keys = result
+            .table_data_2
+            .primary_key
+            .iter()
+            .zip(row.key_values.iter())
+            .map(|(key, (_, value))| (key.as_str(), value));
+        statements.push(PatchStatement {
+            kind: PatchKind::Update,
+            sql: format!(
+                "UPDATE {} SET {} WHERE {};",
+                table_name,
+                assignments.join(", "),
+                key_condition(keys, dialect)
+            ),
+        });
+    }
+    statements
+}
+
+/// write a sql script that makes the second table match the first, optionally
+/// wrapped in a transaction so it is applied all or nothing
+pub fn export_comparison_data_to_sql(
+    result: &ComparisonData,
+    output_file_name: &str,
+    dialect: &SqlDialect,
+    use_transaction: bool,
+    log: &Log,
+) {
+    let file = match File::create(output_file_name) {
+        Ok(file) => file,
+        Err(error) => {
+            panic!("unable to create sql output file {}: {}", output_file_name, error);
+        }
+    };
+    let mut writer = BufWriter::new(file);
+
+    let statements = patch_statements(result, dialect);
+    let mut lines = vec![format!(
+        "-- make {} match {}",
+        result.table_data_2.source_name(),
+        result.table_data_1.source_name()
+    )];
+    if use_transaction {
+        lines.push(match dialect {
+            SqlDialect::MySql => "START TRANSACTION;".to_string(),
+            SqlDialect::Sqlite => "BEGIN TRANSACTION;".to_string(),
+        });
+    }
+    lines.extend(statements.iter().map(|statement| statement.sql.clone()));
+    if use_transaction {
+        lines.push("COMMIT;".to_string());
+    }
+
+    for line in lines {
+        if let Err(error) = writeln!(writer, "{}", line) {
+            panic!("error occurred while writing sql output: {}", error);
+        }
+    }
+    if let Err(error) = writer.flush() {
+        panic!("error occurred while writing sql output: {}", error);
+    }
+    log.info(&format!("exported {} statements to {}", statements.len(), output_file_name));
+}
@@ -1,30 +1,87 @@
 use crate::{
     interface::log::Log,
+    datastore::{
+        data_source::DataSource,
+        transformer::{
+            sqlite_row_to_result_row,
+            sqlite_value_to_value,
+        },
+    },
     models::{
+        changed_row::{
+            ChangedRow,
+            ColumnDifference,
+        },
+        column_mapping::ColumnMapping,
         comparison_data::ComparisonData,
-        table_data::TableData,
+        diff_record::DiffRecord,
+        result_row::ResultRow,
+        table_data::{
+            AttachedDatabase,
+            ColumnData,
+            TableData,
+        },
     }
 };
+use futures::TryStreamExt;
 use sqlx::{
     migrate::MigrateDatabase,
     sqlite::SqlitePoolOptions,
     SqlitePool,
-    Column,
     Pool,
+    Row,
 };
 
-/// open a connection to the sqlite database
-pub(crate) async fn get_connection(log: &Log) -> Pool<sqlx::Sqlite> {
-    let db_url = "sqlite://./db.sqlite3";
+/// open a connection to the sqlite database file at the given path, relative
+/// to the current directory unless the path is absolute
+pub(crate) async fn get_connection(file_name: &str, log: &Log) -> Pool<sqlx::Sqlite> {
+    get_connection_with_attachments(file_name, &[], log).await
+}
+
+/// open a connection to the sqlite database file at the given path with the
+/// given database files attached to every connection in the pool
+pub(crate) async fn get_connection_with_attachments(
+    file_name: &str,
+    attached_databases: &[AttachedDatabase],
+    log: &Log,
+) -> Pool<sqlx::Sqlite> {
+    let db_url = format!("sqlite://{}", file_name);
+    let db_url = db_url.as_str();
     // check if sqlite database exists and create it if it doesn't
     if !sqlx::Sqlite::database_exists(db_url).await.unwrap() {
         sqlx::Sqlite::create_database(db_url).await.unwrap();
         log.info("database did not previously exist, created sqlite db");
     }
 
+    // attach statements are per connection so run them as each connection
+    // in the pool is opened
+    let attach_queries: Vec<String> = attached_databases
+        .iter()
+        .map(|attached_database| {
+            log.info(&format!(
+                "attaching {} as {}",
+                attached_database.file_path, attached_database.alias
+            ));
+            format!(
+                "attach database '{}' as {}",
+                attached_database.file_path.replace('\'', "''"),
+                attached_database.alias
+            )
+        })
+        .collect();
+
     // connect to the sqlite database and return the pool
     let result = SqlitePoolOptions::new()
         .acquire_timeout(std::time::Duration::from_secs(30))
+        .after_connect(move |connection, _| {
+            let attach_queries = attach_queries.clone();
+            Box::pin(async move {
+                for attach_query in attach_queries {
+                    sqlx::query(&attach_query).execute(&mut *connection).await?;
+                }
+                Ok(())
+            })
+        })
         .connect(db_url)
         .await;
     match result {
@@ -35,125 +92,533 @@ pub(crate) async fn get_connection(log: &Log) -> Pool<sqlx::Sqlite> {
     }
 }
 
+/// create a new sqlite staging table with the given columns. The data type of
+/// each column is expected to already be a sqlite type
+pub(crate) async fn create_staging_table(
+    table_name: &str,
+    columns: &[ColumnData],
+    sqlite_pool: &SqlitePool,
+) {
+    let column_definitions: Vec<String> = columns
+        .iter()
+        .map(|column| format!("{} {}", column.name, column.data_type))
+        .collect();
+    let create_query = format!(
+        "create table if not exists {} ({})",
+        table_name,
+        column_definitions.join(",")
+    );
+
+    let result = sqlx::query(create_query.as_str()).execute(sqlite_pool).await;
+    if let Err(error) = result {
+        panic!("error occurred while generating the new sqlite table: {:?}", error);
+    }
+}
+
+/// insert rows of text values into a staging table, binding each value as the
+/// sqlite type of its column. Values that fail to parse as the column type
+/// are inserted as the text they were read as, so they are still compared
+/// rather than turning into nulls
+pub(crate) async fn insert_staging_rows(
+    table_name: &str,
+    columns: &[ColumnData],
+    rows: &[Vec<Option<String>>],
+    sqlite_pool: &SqlitePool,
+) {
+    let column_names: Vec<&str> = columns.iter().map(|column| column.name.as_str()).collect();
+    let placeholders = vec!["?"; column_names.len()].join(",");
+    let insert_query = format!(
+        "insert into {} ({}) values ({})",
+        table_name,
+        column_names.join(","),
+        placeholders
+    );
+
+    // insert every row inside of a single transaction
+    let mut transaction = sqlite_pool.begin().await.unwrap();
+    for row in rows {
+        let mut query = sqlx::query(&insert_query);
+        for (column, value) in columns.iter().zip(row.iter()) {
+            query = match (column.data_type.as_str(), value) {
+                (_, None) => query.bind(None::<String>),
+                ("INTEGER", Some(value)) => match value.parse::<i64>() {
+                    Ok(parsed) => query.bind(parsed),
+                    Err(_) => query.bind(value.clone()),
+                },
+                ("REAL", Some(value)) => match value.parse::<f64>() {
+                    Ok(parsed) => query.bind(parsed),
+                    Err(_) => query.bind(value.clone()),
+                },
+                (_, Some(value)) => query.bind(value.clone()),
+            };
+        }
+
+        if let Err(error) = query.execute(&mut *transaction).await {
+            panic!("error occurred while inserting rows into sqlite table: {:?}", error);
+        }
+    }
+    transaction.commit().await.unwrap();
+}
+
+/// length declared in a column type like `varchar(20)`, None when the type
+/// doesn't declare one
+fn declared_length(data_type: &str) -> Option<i64> {
+    let (_, length) = data_type.split_once('(')?;
+    length.strip_suffix(')')?.trim().parse().ok()
+}
+
+/// quote a table or index name as the string argument of a pragma, so names
+/// with spaces, dots or quotes in them are looked up as they are
+fn pragma_argument(name: &str) -> String {
+    format!("'{}'", name.replace('\'', "''"))
+}
+
+/// Data source that compares a table inside of an existing sqlite database
+/// file in place. The file is attached to the comparison connection so the
+/// rows never have to be copied into a staging table
+pub(crate) struct SqliteSource {
+    /// path to the sqlite database file
+    pub file_path: String,
+    /// name of the table inside of the file
+    pub table_name: String,
+}
+
+impl SqliteSource {
+    pub fn new(file_path: &str, table_name: &str) -> SqliteSource {
+        SqliteSource {
+            file_path: file_path.to_string(),
+            table_name: table_name.to_string(),
+        }
+    }
+
+    /// open a connection to the sqlite database file itself
+    async fn open_file(&self) -> SqlitePool {
+        let db_url = format!("sqlite://{}", self.file_path);
+        if !sqlx::Sqlite::database_exists(&db_url).await.unwrap_or(false) {
+            panic!("sqlite database {} does not exist", self.file_path);
+        }
+
+        match SqlitePoolOptions::new().connect(&db_url).await {
+            Ok(pool) => pool,
+            Err(error) => {
+                panic!("unable to connect to sqlite db {}", error);
+            },
+        }
+    }
+
+    /// columns of the first unique index on the table, ordered by their
+    /// position in the index. Partial indexes are left out since they don't
+    /// cover every row
+    async fn get_unique_index_columns(&self, log: &Log) -> Vec<String> {
+        let pool = self.open_file().await;
+        let index_query = format!("pragma index_list({})", pragma_argument(&self.table_name));
+        let mut indexes: Vec<String> = match sqlx::query(&index_query).fetch_all(&pool).await {
+            Ok(rows) => rows
+                .iter()
+                .filter(|row| row.get::<i64, _>("unique") == 1 && row.get::<i64, _>("partial") == 0)
+                .map(|row| row.get("name"))
+                .collect(),
+            Err(error) => {
+                panic!("error occurred while fetching unique indexes from {:?}", error);
+            },
+        };
+        indexes.sort();
+
+        let Some(index_name) = indexes.first() else {
+            return Vec::new();
+        };
+        log.debug(&format!("joining {} on unique index {}", self.table_name, index_name));
+        let column_query = format!("pragma index_info({})", pragma_argument(index_name));
+        match sqlx::query(&column_query).fetch_all(&pool).await {
+            Ok(rows) => {
+                let mut columns: Vec<(i64, String)> =
+                    rows.iter().map(|row| (row.get("seqno"), row.get("name"))).collect();
+                columns.sort();
+                columns.into_iter().map(|(_, name)| name).collect()
+            },
+            Err(error) => {
+                panic!("error occurred while fetching unique index columns from {:?}", error);
+            },
+        }
+    }
+
+    /// read `pragma table_info` for the table directly out of the file
+    async fn get_table_info(&self, log: &Log) -> Vec<sqlx::sqlite::SqliteRow> {
+        let pool = self.open_file().await;
+        let query = format!("pragma table_info({})", pragma_argument(&self.table_name));
+        match sqlx::query(&query).fetch_all(&pool).await {
+            Ok(rows) => {
+                if rows.is_empty() {
+                    panic!("table {} not found in {}", self.table_name, self.file_path);
+                }
+                log.debug(&format!("found {} columns in {}", rows.len(), self.table_name));
+                rows
+            },
+            Err(error) => {
+                panic!("error occurred while fetching table data from {:?}", error);
+            },
+        }
+    }
+}
+
+impl DataSource for SqliteSource {
+    /// the columns come from `pragma table_info`, with the length read out of
+    /// declared types like `varchar(20)`
+    async fn get_columns(&self, _table_name: &str, log: &Log) -> Vec<ColumnData> {
+        self.get_table_info(log)
+            .await
+            .iter()
+            .map(|row| {
+                let data_type: String = row.get("type");
+                ColumnData {
+                    nullable: Some(row.get::<i64, _>("notnull") == 0 && row.get::<i64, _>("pk") == 0),
+                    max_length: declared_length(&data_type),
+                    ..ColumnData::new(row.get("name"), data_type)
+                }
+            })
+            .collect()
+    }
+
+    /// the primary key is made up of the columns flagged with pk in
+    /// `pragma table_info`, ordered by their position in the key, falling back
+    /// to the columns of the first unique index when there is no primary key
+    async fn get_primary_key(&self, _table_name: &str, log: &Log) -> Vec<String> {
+        let table_info = self.get_table_info(log).await;
+        let mut key_columns: Vec<(i64, String)> = table_info
+            .iter()
+            .filter(|row| row.get::<i64, _>("pk") > 0)
+            .map(|row| (row.get("pk"), row.get("name")))
+            .collect();
+        if !key_columns.is_empty() {
+            key_columns.sort();
+            return key_columns.into_iter().map(|(_, name)| name).collect();
+        }
+
+        log.warn(&format!("no primary key found for {}, looking for a unique index", self.table_name));
+        let unique_index_columns = self.get_unique_index_columns(log).await;
+        if unique_index_columns.is_empty() {
+            panic!("table {} has no primary key or unique index to join on", self.table_name);
+        }
+        unique_index_columns
+    }
+
+    fn source_table(&self, _table_name: &str) -> (String, String) {
+        ("".to_string(), self.table_name.clone())
+    }
+
+    /// the table is compared in place so there is nothing to copy
+    async fn load_rows(&self, query: &str, table_data: &TableData, log: &Log) {
+        if !query.is_empty() {
+            log.warn("queries are not supported for sqlite files, comparing the whole table");
+        }
+        log.info(&format!("comparing {} in place", table_data.query_name()));
+    }
+
+    async fn get_table_data(&self, table_name: &str, key_columns: &[String], log: &Log) -> TableData {
+        let columns = self.get_columns(table_name, log).await;
+        let primary_key = if key_columns.is_empty() {
+            self.get_primary_key(table_name, log).await
+        } else {
+            key_columns.to_vec()
+        };
+        let mut table_data = TableData::new(table_name.to_string(), columns, primary_key);
+        (table_data.source_schema, table_data.source_table) = self.source_table(table_name);
+        table_data.attached_database = Some(AttachedDatabase {
+            file_path: self.file_path.clone(),
+            alias: format!("{}_db", table_name),
+            table_name: self.table_name.clone(),
+        });
+        table_data
+    }
+}
+
 /// Compare two sqlite tables and return the differences
-pub(crate) async fn compare_tables (
+pub(crate) async fn compare_tables(
     table_data_1: &TableData,
     table_data_2: &TableData,
-    mut create_sqlite_comparison_files: bool,
-    in_memory_sqlite: bool,
+    column_mapping: &ColumnMapping,
+    create_sqlite_comparison_files: bool,
     log: &Log,
-    auto_yes: bool
 ) -> ComparisonData {
-    if in_memory_sqlite && create_sqlite_comparison_files {
-        log.info("using in memory sqlite for data comparison,
-             this will be faster but will not save the comparison
-             data to disk, do you want to continue? (yes/no)",
-        );
+    let mut unique_table_1_rows = Vec::new();
+    let mut unique_table_2_rows = Vec::new();
+    let mut changed_rows = Vec::new();
+    let mut comparison_data = stream_comparison(
+        table_data_1,
+        table_data_2,
+        column_mapping,
+        create_sqlite_comparison_files,
+        log,
+        |record| match record {
+            DiffRecord::OnlyLeft { row } => unique_table_1_rows.push(row),
+            DiffRecord::OnlyRight { row } => unique_table_2_rows.push(row),
+            DiffRecord::Changed(row) => changed_rows.push(row),
+        },
+    )
+    .await;
 
-        // read from std in
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input).unwrap();
-        //TODO: take in input args auto-yes flag to pass down to here
-        if input == "yes\n" || input == "y" || auto_yes {
-            log.info("continuing with in memory sqlite");
-            create_sqlite_comparison_files = false;
-        } else {
-            log.info("exiting program");
-            std::process::exit(0);
-        }
+    comparison_data.unique_table_1_rows = unique_table_1_rows;
+    comparison_data.unique_table_2_rows = unique_table_2_rows;
+    comparison_data.changed_rows = changed_rows;
+    comparison_data
+}
+
+/// Compare two sqlite tables, passing each difference to `on_record` as soon
+/// as it is read rather than collecting them. The returned comparison data
+/// describes the tables and holds the unchanged row count but none of the
+/// differing rows
+pub(crate) async fn stream_comparison<F: FnMut(DiffRecord)>(
+    table_data_1: &TableData,
+    table_data_2: &TableData,
+    column_mapping: &ColumnMapping,
+    create_sqlite_comparison_files: bool,
+    log: &Log,
+    mut on_record: F,
+) -> ComparisonData {
+    if table_data_1.primary_key.len() != table_data_2.primary_key.len() {
+        panic!(
+            "tables {} and {} have a different number of key columns ({} and {})",
+            table_data_1.table_name,
+            table_data_2.table_name,
+            table_data_1.primary_key.join(","),
+            table_data_2.primary_key.join(",")
+        );
     }
 
-    // get the sqlite connection, and execute each part of the comparison
-    let sqlite_pool = self::get_connection(log).await;
+    // get the sqlite connection with any tables being compared in place
+    // attached, and execute each part of the comparison
+    let attached_databases: Vec<AttachedDatabase> = [table_data_1, table_data_2]
+        .iter()
+        .filter_map(|table_data| table_data.attached_database.clone())
+        .collect();
+    let sqlite_pool =
+        self::get_connection_with_attachments(&table_data_1.sqlite_file_name, &attached_databases, log).await;
 
-    let comparison_data = ComparisonData::new(
-        get_unique_rows(
-            table_data_1,
-            table_data_2,
-            &sqlite_pool,
-            create_sqlite_comparison_files,
-            log,
-        )
-        .await,
-        get_unique_rows(
-            table_data_2,
-            table_data_1,
-            &sqlite_pool,
-            create_sqlite_comparison_files,
-            log,
-        )
-        .await,
-        get_changed_rows(
-            table_data_1,
-            table_data_2,
-            &sqlite_pool,
-            create_sqlite_comparison_files,
-            log,
-        )
-        .await,
-    );
+    get_unique_rows(
+        table_data_1,
+        table_data_2,
+        &sqlite_pool,
+        create_sqlite_comparison_files,
+        log,
+        &mut |row| on_record(DiffRecord::OnlyLeft { row }),
+    )
+    .await;
+    get_unique_rows(
+        table_data_2,
+        table_data_1,
+        &sqlite_pool,
+        create_sqlite_comparison_files,
+        log,
+        &mut |row| on_record(DiffRecord::OnlyRight { row }),
+    )
+    .await;
+    get_changed_rows(
+        table_data_1,
+        table_data_2,
+        column_mapping,
+        &sqlite_pool,
+        create_sqlite_comparison_files,
+        log,
+        &mut |row| on_record(DiffRecord::Changed(row)),
+    )
+    .await;
+
+    let mut comparison_data = ComparisonData::new(Vec::new(), Vec::new(), Vec::new());
+    comparison_data.unchanged_row_count =
+        get_unchanged_row_count(table_data_1, table_data_2, column_mapping, &sqlite_pool, log).await;
+    comparison_data.table_data_1 = table_data_1.clone();
+    comparison_data.table_data_2 = table_data_2.clone();
+    comparison_data.column_mapping = column_mapping.clone();
+    comparison_data.unmapped_table_1_columns = column_mapping.unmapped_table_1_columns.clone();
+    comparison_data.unmapped_table_2_columns = column_mapping.unmapped_table_2_columns.clone();
 
-    generate_main_comparison_file(table_data_1, table_data_2, &sqlite_pool, log).await;
+    generate_main_comparison_file(table_data_1, table_data_2, column_mapping, &sqlite_pool, log).await;
     comparison_data
 }
 
 
 
-/// Get the rows that where the two primary keys match but the other columns differ
+/// generate the condition to join two tables on their keys, pairing up the
+/// key columns of each table by position
+/// e.g. `t1.tenant_id = t2.tenant_id and t1.sku = t2.sku`
+pub(crate) fn key_join_condition(
+    sqlite_table_1: &TableData,
+    sqlite_table_2: &TableData,
+    alias_1: &str,
+    alias_2: &str,
+) -> String {
+    sqlite_table_1
+        .primary_key
+        .iter()
+        .zip(sqlite_table_2.primary_key.iter())
+        .map(|(key_1, key_2)| format!("{}.{} = {}.{}", alias_1, key_1, alias_2, key_2))
+        .collect::<Vec<String>>()
+        .join(" and ")
+}
+
+/// generate the condition that is true when at least one of the mapped
+/// columns differs between the two aliases. `is not` is used so that a null on
+/// one side and a value on the other counts as a difference while two nulls
+/// do not. When there are no columns to compare nothing can differ
+pub(crate) fn changed_columns_condition(column_mapping: &ColumnMapping, alias_1: &str, alias_2: &str) -> String {
+    if column_mapping.pairs.is_empty() {
+        return "0".to_string();
+    }
+
+    let conditions: Vec<String> = column_mapping
+        .pairs
+        .iter()
+        .map(|pair| format!("{}.{} is not {}.{}", alias_1, pair.column_1, alias_2, pair.column_2))
+        .collect();
+    format!("({})", conditions.join(" or "))
+}
+
+/// count the rows where the keys match and every mapped column is the same
+async fn get_unchanged_row_count(
+    sqlite_table_1: &TableData,
+    sqlite_table_2: &TableData,
+    column_mapping: &ColumnMapping,
+    sqlite_pool: &SqlitePool,
+    log: &Log,
+) -> usize {
+    let count_query = format!(
+        "select count(*)
+        from {} t1
+        where exists (
+            select 1 from {} t2 where {} and not {}
+        )",
+        sqlite_table_1.query_name(),
+        sqlite_table_2.query_name(),
+        key_join_condition(sqlite_table_1, sqlite_table_2, "t1", "t2"),
+        changed_columns_condition(column_mapping, "t1", "t2")
+    );
+
+    match sqlx::query(&count_query).fetch_one(sqlite_pool).await {
+        Ok(row) => {
+            let unchanged_row_count: i64 = row.get(0);
+            log.info(&format!("counted {} unchanged rows", unchanged_row_count));
+            unchanged_row_count as usize
+        }
+        Err(error) => {
+            panic!("error: {:?}", error);
+        }
+    }
+}
+
+/// Get the rows that where the two primary keys match but at least one of the
+/// mapped columns differ, along with the values on each side of every column
+/// that differs, passing each one to `on_row` as it is read
 async fn get_changed_rows(
     sqlite_table_1: &TableData,
     sqlite_table_2: &TableData,
+    column_mapping: &ColumnMapping,
     sqlite_pool: &SqlitePool,
     create_sqlite_comparison_files: bool,
     log: &Log,
-) -> Vec<sqlx::sqlite::SqliteRow> {
+    on_row: &mut dyn FnMut(ChangedRow),
+) {
+    // select the key columns followed by the value on each side of every
+    // mapped column and whether the two values differ
+    let mut select_columns: Vec<String> = sqlite_table_1
+        .primary_key
+        .iter()
+        .map(|key| format!("t1.{} as {}", key, key))
+        .collect();
+    for pair in column_mapping.pairs.iter() {
+        select_columns.push(format!("t1.{} as {}__1", pair.column_1, pair.column_1));
+        select_columns.push(format!("t2.{} as {}__2", pair.column_2, pair.column_1));
+        select_columns.push(format!(
+            "t1.{} is not t2.{} as {}__changed",
+            pair.column_1, pair.column_2, pair.column_1
+        ));
+    }
+
+    let changed_rows_select = format!(
+        "select {}
+        from {} t1
+        join {} t2 on {}
+        where {}",
+        select_columns.join(","),
+        sqlite_table_1.query_name(),
+        sqlite_table_2.query_name(),
+        key_join_condition(sqlite_table_1, sqlite_table_2, "t1", "t2"),
+        changed_columns_condition(column_mapping, "t1", "t2")
+    );
+
     let select_query = if create_sqlite_comparison_files {
         format!("
             create table changedRows_{}
             as
-            select *
-            from {}
-            where exists (
-                select * from {} where {} = {}
-            );
+            {};
             select * from changedRows_{}
             ",
             sqlite_table_1.table_name,
-            sqlite_table_1.table_name,
-            sqlite_table_2.table_name,
-            sqlite_table_1.primary_key,
-            sqlite_table_2.primary_key,
+            changed_rows_select,
             sqlite_table_1.table_name
         )
     } else {
-        format!("
-            select *
-            from {}
-            where exists (
-                select * from {} where {} = {}
-            );",
-            sqlite_table_1.table_name,
-            sqlite_table_2.table_name,
-            sqlite_table_1.primary_key,
-            sqlite_table_2.primary_key
-        )
-    }.to_string();
+        changed_rows_select
+    };
 
-    // execute select query
-    let rows = sqlx::query(select_query.as_str())
-        .fetch_all(sqlite_pool)
-        .await;
+    // execute select query and hand each changed row out as it is read
+    let mut rows = sqlx::query(select_query.as_str()).fetch(sqlite_pool);
+    let key_count = sqlite_table_1.primary_key.len();
+    let mut row_count = 0;
+    loop {
+        let row = match rows.try_next().await {
+            Ok(Some(row)) => row,
+            Ok(None) => break,
+            Err(error) => {
+                panic!("error: {:?}", error);
+            }
+        };
 
-    // if no errors return the rows otherwise return that there was an error
-    match rows {
-        Ok(rows) =>{
-            log.info(&format!("extracted {} changed rows", rows.len()));
-            rows
-        }
-        Err(error) => {
-            panic!("error: {:?}", error);
-        }
+        let key_values = sqlite_table_1
+            .primary_key
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                (key.clone(), sqlite_value_to_value(&row, i, &sqlite_table_1.source_type_of(key)))
+            })
+            .collect();
+
+        let is_changed = |i: usize| row.get::<i64, _>(key_count + i * 3 + 2) == 1;
+        let unchanged_values = column_mapping
+            .pairs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !is_changed(*i))
+            .map(|(i, pair)| {
+                let value = sqlite_value_to_value(&row, key_count + i * 3, &sqlite_table_1.source_type_of(&pair.column_1));
+                (pair.column_1.clone(), value)
+            })
+            .collect();
+        let differences = column_mapping
+            .pairs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| is_changed(*i))
+            .map(|(i, pair)| ColumnDifference {
+                column: pair.column_1.clone(),
+                left_value: sqlite_value_to_value(
+                    &row,
+                    key_count + i * 3,
+                    &sqlite_table_1.source_type_of(&pair.column_1),
+                ),
+                right_value: sqlite_value_to_value(
+                    &row,
+                    key_count + i * 3 + 1,
+                    &sqlite_table_2.source_type_of(&pair.column_2),
+                ),
+            })
+            .collect();
+
+        let mut changed_row = ChangedRow::new(key_values, differences);
+        changed_row.unchanged_values = unchanged_values;
+        on_row(changed_row);
+        row_count += 1;
     }
+    log.info(&format!("extracted {} changed rows", row_count));
 }
 
 /// take the currently generated in flight files and combine them into one
@@ -176,18 +641,28 @@ async fn get_changed_rows(
 async fn generate_main_comparison_file(
     sqlite_table_1: &TableData,
     sqlite_table_2: &TableData,
+    column_mapping: &ColumnMapping,
     sqlite_pool: &SqlitePool,
     log: &Log,
 ) -> Vec<sqlx::sqlite::SqliteRow> {
-    // initialize the main output query
+    // initialize the main output query, named after the first table so that
+    // comparisons of different tables run at the same time don't collide
     let mut comparison_query = format!(
-        "create table main_out_{} as select ",
+        "create table main_out_{}_{} as select ",
+        sqlite_table_1.table_name,
         chrono::offset::Local::now().timestamp()
     );
 
-    // iterate through the columns and generate the query to output the differences in tables
-    sqlite_table_1.columns.iter().for_each(|column| {
-        let column_name = column.name();
+    // key columns are output as they are since they are the same on both sides
+    for key in sqlite_table_1.primary_key.iter() {
+        comparison_query.push_str(&format!("t1.{} as {},", key, key));
+    }
+
+    // iterate through the mapped columns and generate the query to output the
+    // differences in tables, named after the column in the first table
+    column_mapping.pairs.iter().for_each(|pair| {
+        let column_1 = &pair.column_1;
+        let column_2 = &pair.column_2;
         let query_column = format!(
             "case
                 when t1.{} is null and t2.{} is not null then '()'||t2.{}
@@ -195,18 +670,18 @@ async fn generate_main_comparison_file(
                 when t1.{} != t2.{} then t1.{}||'('||t2.{}||')'
             else t1.{}
             end as {},",
-            column_name,
-            column_name,
-            column_name,
-            column_name,
-            column_name,
-            column_name,
-            column_name,
-            column_name,
-            column_name,
-            column_name,
-            column_name,
-            column_name,
+            column_1,
+            column_2,
+            column_2,
+            column_1,
+            column_2,
+            column_1,
+            column_1,
+            column_2,
+            column_1,
+            column_2,
+            column_1,
+            column_1,
         );
         comparison_query.push_str(&query_column);
     });
@@ -215,16 +690,11 @@ async fn generate_main_comparison_file(
     let changed_rows_join = format!(
         "
         from {} t1
-        left join {} t2 on t1.{} = t2.{}
-        left join unique_{} new on t1.{} = new.{}
+        left join {} t2 on {}
         ",
-        sqlite_table_1.table_name,
-        sqlite_table_2.table_name,
-        sqlite_table_1.primary_key,
-        sqlite_table_2.primary_key,
-        sqlite_table_1.table_name,
-        sqlite_table_1.primary_key,
-        sqlite_table_1.primary_key,
+        sqlite_table_1.query_name(),
+        sqlite_table_2.query_name(),
+        key_join_condition(sqlite_table_1, sqlite_table_2, "t1", "t2"),
     );
     comparison_query.push_str(&changed_rows_join);
 
@@ -243,7 +713,8 @@ async fn generate_main_comparison_file(
     }
 }
 
-/// Gets the rows that are unique to the first table and do not eixst in the second
+/// Gets the rows that are unique to the first table and do not eixst in the second,
+/// passing each one to `on_row` as it is read
 /// If create_sqlite_comparison_files is true then the rows are saved to a new table
 /// called unique_{table_name}
 async fn get_unique_rows(
@@ -252,59 +723,66 @@ async fn get_unique_rows(
     sqlite_pool: &SqlitePool,
     create_sqlite_comparison_files: bool,
     log: &Log,
-) -> Vec<sqlx::sqlite::SqliteRow> {
+    on_row: &mut dyn FnMut(ResultRow),
+) {
+    let join_condition = key_join_condition(sqlite_table_1, sqlite_table_2, "t1", "t2");
     let select_query = if create_sqlite_comparison_files {
         // generate select statement and join on the primary key
         format!(
             "create table unique_{}
             as
-            select *
-            from {}
+            select t1.*
+            from {} t1
             where not exists (
-                select * from {} where {} = {}
+                select 1 from {} t2 where {}
             );
             select * from unique_{}",
             sqlite_table_1.table_name,
-            sqlite_table_1.table_name,
-            sqlite_table_2.table_name,
-            sqlite_table_1.primary_key,
-            sqlite_table_2.primary_key,
+            sqlite_table_1.query_name(),
+            sqlite_table_2.query_name(),
+            join_condition,
             sqlite_table_1.table_name
         )
     } else {
         format!(
-            "select *
-            from {}
+            "select t1.*
+            from {} t1
             where not exists (
-                select * from {} where {} = {}
+                select 1 from {} t2 where {}
             );",
-            sqlite_table_2.table_name,
-            sqlite_table_1.table_name,
-            sqlite_table_1.primary_key,
-            sqlite_table_2.primary_key
+            sqlite_table_1.query_name(),
+            sqlite_table_2.query_name(),
+            join_condition
         )
     };
 
-    // execute select query
-    let rows = sqlx::query(select_query.as_str())
-        .fetch_all(sqlite_pool)
-        .await;
-
-    // if no errors return the rows otherwise return that there was an error
-    match rows {
-        Ok(rows) => {
-            log.info(&format!("extracted {} unique rows", rows.len()));
-            rows
-        }
-        Err(error) => {
-            panic!("error: {:?}", error);
+    // execute select query and hand each row out as it is read
+    let mut rows = sqlx::query(select_query.as_str()).fetch(sqlite_pool);
+    let mut row_count = 0;
+    loop {
+        match rows.try_next().await {
+            Ok(Some(row)) => {
+                on_row(sqlite_row_to_result_row(&row, sqlite_table_1));
+                row_count += 1;
+            }
+            Ok(None) => break,
+            Err(error) => {
+                panic!("error: {:?}", error);
+            }
         }
     }
+    log.info(&format!("extracted {} unique rows", row_count));
 }
 
 
-/// Cleans up all sqlite files inside the current executing directory
-pub(crate) async fn clear_sqlite_data(){
+/// Cleans up all sqlite files inside the current executing directory, except
+/// for the files passed in that are being compared in place
+pub(crate) async fn clear_sqlite_data(keep_files: &[&str]){
+    let keep_files: Vec<std::path::PathBuf> = keep_files
+        .iter()
+        .filter_map(|file_path| std::fs::canonicalize(file_path).ok())
+        .collect();
+
     // get all files in the current directory
     let files = std::fs::read_dir(".").unwrap();
     for file in files{
@@ -312,7 +790,12 @@ pub(crate) async fn clear_sqlite_data(){
         let file_name = file.file_name();
         let file_name = file_name.to_str().unwrap();
         if file_name.ends_with(".sqlite"){
-            std::fs::remove_file(file_name).unwrap();
+            let is_compared = std::fs::canonicalize(file_name)
+                .map(|file_path| keep_files.contains(&file_path))
+                .unwrap_or(false);
+            if !is_compared {
+                std::fs::remove_file(file_name).unwrap();
+            }
         }
     }
 }
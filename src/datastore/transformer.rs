@@ -1,142 +1,177 @@
 use crate::{
     interface::log::Log,
-    models::table_data::TableData,
-    datastore::{
-        sqlite,
-        generator
-    }
+    models::{
+        result_row::ResultRow,
+        table_data::{
+            ColumnData,
+            TableData,
+        },
+        value::Value,
+    },
+    datastore::sqlite,
 };
 
 use sqlx::{
     mysql::MySqlRow,
+    types::time::{
+        Date,
+        PrimitiveDateTime,
+        Time,
+    },
     sqlite::SqliteRow,
     Row,
     Column,
-    TypeInfo
+    TypeInfo,
+    ValueRef,
 };
 
-pub fn sqlite_row_to_string_vec(row:&SqliteRow, log: &Log) -> Vec<String> {
-    // convert sqliteRow to csv row
-    let mut csv_row = Vec::new();
-    let number_of_columns = row.columns().len();
-    for i in 0..number_of_columns {
-        let column_type = row.column(i).type_info().to_string();
-        match column_type.as_str() {
-            "TEXT" => {
-                let value: String = row.get(i);
-                csv_row.push(value);
-            }
-            "INTEGER" => {
-                let value: i64 = row.get(i);
-                csv_row.push(value.to_string());
-            }
-            "REAL" => {
-                let value: f64 = row.get(i);
-                csv_row.push(value.to_string());
-            }
-            "BLOB" => {
-                let value: String = row.get(i);
-                csv_row.push(value);
-            }
-            _ => {
-                log.error(&format!("unknown column type: {}", column_type));
-            }
-        }
+/// read the value at the given index of a sqlite row into an owned value.
+/// The value is read based on its own storage class rather than the declared
+/// column type, and text values are interpreted using the type the column had
+/// in the source table so dates and decimals survive the trip through sqlite
+pub fn sqlite_value_to_value(row: &SqliteRow, index: usize, source_type: &str) -> Value {
+    let value = row.try_get_raw(index).unwrap();
+    if value.is_null() {
+        return Value::Null;
+    }
+
+    let value_type = value.type_info().name().to_string();
+    match value_type.as_str() {
+        "INTEGER" => Value::Int(row.get(index)),
+        "REAL" => Value::Float(row.get(index)),
+        "BLOB" => Value::Bytes(row.get(index)),
+        _ => Value::from_text(row.get(index), source_type),
     }
-    // finally return csv row
-    csv_row
 }
 
-/// Converts a batch of MySql rows to a sqlite new sqlite table
-/// and inserts the rows into the new table
+/// convert a sqlite row read out of one of the tables into an owned result
+/// row, using the columns of the table to find the source type of each value
+pub fn sqlite_row_to_result_row(row: &SqliteRow, table_data: &TableData) -> ResultRow {
+    let columns: Vec<String> = row.columns().iter().map(|column| column.name().to_string()).collect();
+    let values = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column_name)| sqlite_value_to_value(row, i, &table_data.source_type_of(column_name)))
+        .collect();
+    ResultRow::new(columns, values)
+}
+
+/// Stage rows read out of mysql in a new sqlite table. The columns of the
+/// staging table are read from the rows so a query can select or rename
+/// columns, without rows the columns of the table are used instead
 pub(crate) async fn mysql_table_to_sqlite_table(
-    mysql_rows: &Vec<MySqlRow>,
+    mysql_rows: &[MySqlRow],
     table_data: &TableData,
     log: &Log,
 ) {
-    // open a new sqlite connection and execute the create statment
-    let sqlite_pool = sqlite::get_connection(log).await;
+    let sqlite_columns: Vec<ColumnData> = match mysql_rows.first() {
+        Some(row) => row
+            .columns()
+            .iter()
+            .map(|column| ColumnData::new(column.name().to_string(), mysql_type_to_sqlite_type(column.type_info().name())))
+            .collect(),
+        None => table_data
+            .columns
+            .iter()
+            .map(|column| ColumnData::new(column.name.clone(), mysql_type_to_sqlite_type(&column.data_type)))
+            .collect(),
+    };
+    let values: Vec<Vec<Option<String>>> = mysql_rows
+        .iter()
+        .map(|row| (0..row.columns().len()).map(|i| mysql_value_to_text(row, i)).collect())
+        .collect();
 
-    // if we've built the new sqlite table
-    if generator::export_mysql_rows_to_sqlite_table(mysql_rows, &sqlite_pool, &table_data.table_name).await {
-        log.info(&format!("created new sqlite table: {}", &table_data.table_name));
+    let sqlite_pool = sqlite::get_connection(&table_data.sqlite_file_name, log).await;
+    sqlite::create_staging_table(&table_data.table_name, &sqlite_columns, &sqlite_pool).await;
+    log.info(&format!("created new sqlite table: {}", &table_data.table_name));
+    sqlite::insert_staging_rows(&table_data.table_name, &sqlite_columns, &values, &sqlite_pool).await;
+    log.info(&format!("loaded {} rows into {}", values.len(), &table_data.table_name));
+}
 
-        // generate the insert query and run it
-        let insert_query = create_sqlite_insert_query_from_mysql_row(mysql_rows, &table_data.table_name);
-        let result = sqlx::query(insert_query.as_str()).execute(&sqlite_pool).await;
+/// read the value at the given index of a mysql row as the text it is staged
+/// as, decoding it by the type of its column. Booleans are staged as 1/0,
+/// dates and times in the format `Value::from_text` reads them back in and
+/// binary values as hex, the way postgres writes bytea out as text
+fn mysql_value_to_text(row: &MySqlRow, index: usize) -> Option<String> {
+    let column = &row.columns()[index];
+    let column_type = column.type_info().name();
+    let value = match column_type {
+        "BOOLEAN" => row
+            .try_get::<Option<bool>, _>(index)
+            .map(|value| value.map(|value| if value { "1" } else { "0" }.to_string())),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => row
+            .try_get::<Option<i64>, _>(index)
+            .map(|value| value.map(|value| value.to_string())),
+        "YEAR" | "BIT" => row
+            .try_get_unchecked::<Option<u64>, _>(index)
+            .map(|value| value.map(|value| value.to_string())),
+        unsigned_type if unsigned_type.ends_with(" UNSIGNED") => row
+            .try_get::<Option<u64>, _>(index)
+            .map(|value| value.map(|value| value.to_string())),
+        "FLOAT" => row
+            .try_get::<Option<f32>, _>(index)
+            .map(|value| value.map(|value| value.to_string())),
+        "DOUBLE" => row
+            .try_get::<Option<f64>, _>(index)
+            .map(|value| value.map(|value| value.to_string())),
+        "DATE" => row
+            .try_get::<Option<Date>, _>(index)
+            .map(|value| value.map(|value| value.to_string())),
+        "TIME" => row
+            .try_get::<Option<Time>, _>(index)
+            .map(|value| value.map(format_time)),
+        "DATETIME" | "TIMESTAMP" => row
+            .try_get::<Option<PrimitiveDateTime>, _>(index)
+            .map(|value| value.map(|value| format!("{} {}", value.date(), format_time(value.time())))),
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "GEOMETRY" => row
+            .try_get_unchecked::<Option<Vec<u8>>, _>(index)
+            .map(|value| value.map(|value| {
+                let hex: String = value.iter().map(|byte| format!("{:02x}", byte)).collect();
+                format!("\\x{}", hex)
+            })),
+        // decimals, json and the text types are all sent as text
+        &_ => row.try_get_unchecked::<Option<String>, _>(index),
+    };
 
-        match result {
-            Ok(_) => {},
-            Err(error) => {
-                panic!("error occurred while inserting rows into sqlite table: {:?}", error);
-            },
-        }
-    } else {
-        panic!("unable to create new sqlite table");
+    match value {
+        Ok(value) => value,
+        Err(error) => {
+            panic!("error occurred while reading {} column {}: {:?}", column_type, column.name(), error);
+        },
     }
 }
 
-// generates a new sqlite insert query from a passed in mysql row
-fn create_sqlite_insert_query_from_mysql_row(mysql_rows: &Vec<MySqlRow>, table_name: &str) -> String {
-    // for each row in mysql generate the insert statement
-    let mut insert_query = format!("insert into {} (", table_name);
-
-    // generate the column insert list
-    for column in mysql_rows[0].columns() {
-        insert_query.push_str(column.name());
-        insert_query.push(',');
+/// format a time as `%H:%M:%S%.f`, leaving the fraction out when it is zero
+fn format_time(time: Time) -> String {
+    let (hour, minute, second, microsecond) = time.as_hms_micro();
+    if microsecond == 0 {
+        format!("{:02}:{:02}:{:02}", hour, minute, second)
+    } else {
+        format!("{:02}:{:02}:{:02}.{:06}", hour, minute, second, microsecond)
     }
+}
 
-    insert_query.pop();
-    insert_query.push_str(") VALUES ");
-
-    // foreach row in the mysql result set generate the insert query
-    for row in mysql_rows {
-        let mut value_insert_string = "(".to_string();
-
-        // generate the list of values to insert
-        for column in row.columns() {
-            let column_name = column.name();
-            let column_type = column.type_info().name();
-
-            // TODO: add support for other column types here
-            match column_type {
-                "INT" => {
-                    let value: i32 = row.get(column_name);
-                    value_insert_string.push_str(&value.to_string());
-                    value_insert_string.push(',');
-                },
-                "VARCHAR" => {
-                    let value: String = row.get(column_name);
-                    value_insert_string.push('\'');
-                    value_insert_string.push_str(&value);
-                    value_insert_string.push_str("',");
-                },
-                &_ => {
-                    let value: String = row.get(column_name);
-                    value_insert_string.push('\'');
-                    value_insert_string.push_str(&value);
-                    value_insert_string.push_str("',");
-                },
-            }
-        }
-
-        // remove trailing comma and add closing parens
-        value_insert_string.pop();
-        value_insert_string.push_str("),");
-
-        insert_query.push_str(value_insert_string.as_str());
+/// map a mysql type, as named by sqlx or by `get_columns`, to the sqlite type
+/// used in the staging table. decimals, dates and times are staged as text
+pub fn mysql_type_to_sqlite_type(mysql_type: &str) -> String {
+    match mysql_type.trim_end_matches(" UNSIGNED") {
+        "BOOLEAN" | "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "INTEGER" | "BIGINT" | "YEAR" | "BIT" => {
+            "INTEGER".to_string()
+        },
+        "FLOAT" | "DOUBLE" | "REAL" => "REAL".to_string(),
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "GEOMETRY" => "BLOB".to_string(),
+        &_ => "TEXT".to_string(),
     }
-    // remove trailing comma
-    insert_query.pop();
-    insert_query
 }
 
-pub fn mysql_type_to_sqlite_type(mysql_type: &str) -> String {
-    //TODO: add support for other column types here
-    match mysql_type {
-        "INT" => "INTEGER".to_string(),
-        "VARCHAR" => "TEXT".to_string(),
-        &_ => "BLOB".to_string(),
+/// map a postgres type, as named in information_schema.columns or by its
+/// short name in pg_type, to the sqlite type used in the staging table.
+/// numeric is staged as text
+pub fn postgres_type_to_sqlite_type(postgres_type: &str) -> String {
+    match postgres_type {
+        "smallint" | "integer" | "bigint" | "boolean" | "int2" | "int4" | "int8" | "bool" => "INTEGER".to_string(),
+        "real" | "double precision" | "float4" | "float8" => "REAL".to_string(),
+        "bytea" => "BLOB".to_string(),
+        &_ => "TEXT".to_string(),
     }
 }
@@ -1,13 +1,65 @@
 use chrono::Local;
 use std::process::exit;
-use crate::interface::log_options::LogOutput;
+use std::path::Path;
+use crate::{
+    interface::{
+        config::Config,
+        log_options::{
+            LogOutput,
+            LogVerbosity,
+        },
+    },
+    datastore::csv::{
+        sanitize_name,
+        CsvOptions,
+    },
+    models::table_data::DEFAULT_SQLITE_FILE_NAME,
+};
 
+#[derive(Clone)]
 pub enum OutputFileType {
     Csv,
-    Json
+    Json,
+    Ndjson,
+    Html,
+    Sql,
+    Parquet,
+}
+
+/// How the csv output lays the differences out
+#[derive(Clone)]
+pub enum CsvLayout {
+    /// separate files for the rows unique to each table and the changed rows
+    Split,
+    /// a single file with a change type column and side by side values
+    Unified,
+}
+
+/// Flavour of sql written by the patch script output
+#[derive(Clone)]
+pub enum SqlDialect {
+    MySql,
+    Sqlite,
+}
+
+/// The backend a side of the comparison is read from
+#[derive(Clone)]
+pub enum SourceType {
+    /// mysql table named after the staging table, which is the table `-gen`
+    /// fills when no table is passed in
+    MySql,
+    /// mysql table found at the given `schema.table` reference
+    MySqlTable(String),
+    /// postgres table found at the given `schema.table` reference
+    Postgres(String),
+    /// csv file found at the given path
+    Csv(String),
+    /// table inside of the sqlite database file found at the given path
+    Sqlite(String, String),
 }
 
 /// Struct to hold the arguments passed in from the command line
+#[derive(Clone)]
 pub struct Arguments {
     /// run with terminalUi
     pub tui: bool,
@@ -17,6 +69,21 @@ pub struct Arguments {
 
     pub output_file_type: OutputFileType,
 
+    /// path to write a junit xml report of the comparison to
+    pub junit_file_name: String,
+
+    /// layout of the csv output
+    pub csv_layout: CsvLayout,
+
+    /// delimiter, quoting, null value and line ending of the csv output
+    pub csv_options: CsvOptions,
+
+    /// dialect of the statements written when exporting a sql patch script
+    pub sql_dialect: SqlDialect,
+
+    /// flag to wrap the sql patch script in a transaction
+    pub sql_transaction: bool,
+
     /// MySql query to run to generate the first tabl
     pub mysql_query_1: String,
 
@@ -47,6 +114,27 @@ pub struct Arguments {
     /// name of the second table to compare
     pub table_name_2: String,
 
+    /// source the first table is read from
+    pub source_type_1: SourceType,
+
+    /// source the second table is read from
+    pub source_type_2: SourceType,
+
+    /// url of the mysql or postgres database the first table is read from,
+    /// empty to use the connection string env var of the source
+    pub connection_url_1: String,
+
+    /// url of the mysql or postgres database the second table is read from,
+    /// empty to use the connection string env var of the source
+    pub connection_url_2: String,
+
+    /// columns to join both tables on instead of the discovered key
+    pub key_columns: Vec<String>,
+
+    /// pairs of differently named columns to compare against each other, as
+    /// (table 1 column, table 2 column)
+    pub column_mapping: Vec<(String, String)>,
+
     /// flag to create sqlite comparison files while in flight
     pub create_sqlite_comparison_files: bool,
 
@@ -56,8 +144,50 @@ pub struct Arguments {
     /// flag to auto select yes to all prompts
     pub auto_yes: bool,
 
+    /// flag to apply the differences to the second table in mysql so it
+    /// matches the first
+    pub sync: bool,
+
+    /// flag to report what sync would change without applying anything
+    pub dry_run: bool,
+
+    /// flag to only compare the schemas of the tables without comparing rows
+    pub schema_only: bool,
+
     /// log output type to allow user to configure where they want logs to go
-    pub log_output: LogOutput
+    pub log_output: LogOutput,
+
+    /// lowest level of log messages to write
+    pub log_verbosity: LogVerbosity,
+
+    /// name of the job when this is one of the jobs of a config file
+    pub job_name: String,
+
+    /// sqlite database file the tables are staged and compared in
+    pub sqlite_file_name: String,
+
+    /// comparisons declared as jobs in a config file, run instead of the
+    /// single comparison described by the rest of the arguments
+    pub jobs: Vec<Arguments>,
+
+    /// flag to run the jobs at the same time instead of one after another
+    pub concurrent_jobs: bool,
+
+    /// first mysql database to compare every table of
+    pub database_1: String,
+
+    /// second mysql database to compare every table of
+    pub database_2: String,
+
+    /// glob patterns of the tables to compare when comparing whole databases,
+    /// every table is compared when empty
+    pub include_tables: Vec<String>,
+
+    /// glob patterns of the tables to leave out when comparing whole databases
+    pub exclude_tables: Vec<String>,
+
+    /// path to write the database level report of a whole database comparison to
+    pub database_report_file_name: String,
 }
 
 /// prints the urrent flags and their descriptions
@@ -67,18 +197,142 @@ pub(crate) fn print_help(){
     println!("\t-h : print this help message");
     println!("\t-help : print this help message");
     println!("\t-tui : run with terminal ui");
-    println!("\t-q1=<query> : specify a first mysql query to run");
-    println!("\t-q2=<query> : specify a second mysql query to run");
+    println!("\t-q1=<query> : specify a first mysql query to run, not for csv files");
+    println!("\t-q2=<query> : specify a second mysql query to run, not for csv files");
     println!("\t-gen : generate new data in mysql");
-    println!("\t-verbose : verbose output");
+    println!("\t-verbose : verbose output, same as -log-level=debug");
+    println!("\t-log-level=<level> : lowest level of log messages to write (debug, info, warning, error)");
     println!("\t-version : print version information");
-    println!("\t-c : clean sqlite database");
-    println!("\t-t1=<table_name> : specify the name of the first table to compare");
-    println!("\t-t2=<table_name> : specify the name of the second table to compare");
+    println!("\t-c : clean sqlite database, on by default");
+    println!("\t-t1=<table_name> : specify the first table to compare, the value picks the source: a mysql table name, mysql:<schema.table>, postgres:<schema.table>, a path to a .csv file or <path.sqlite>:<table>");
+    println!("\t-t2=<table_name> : specify the second table to compare, the value picks the source: a mysql table name, mysql:<schema.table>, postgres:<schema.table>, a path to a .csv file or <path.sqlite>:<table>");
+    println!("\t-url1=<url> : url of the mysql or postgres database the first table is read from (DCT_LEFT_URL)");
+    println!("\t-url2=<url> : url of the mysql or postgres database the second table is read from (DCT_RIGHT_URL)");
+    println!("\t-key=<column,column> : join both tables on these columns instead of their primary keys");
+    println!("\t-map=<column_1:column_2,...> : compare column_1 in the first table against column_2 in the second");
     println!("\t-in-memory : use an in memory sqlite database instead of file based");
-    println!("\t-create-in-flight : create sqlite comparison files while in flight");
+    println!("\t-create-in-flight : create sqlite comparison files while in flight, on by default");
+    println!("\t-no-<switch> : turn off a switch that is on by default or was turned on by the config file or an env var, e.g. -no-c");
     println!("\t-auto-yes : automatically answer yes to all prompts");
-    println!("\t-output=<output_file> : specify the name of the output csv file");
+    println!("\t-sync : apply the inserts, updates and deletes that make table 2 match table 1 in mysql, not with .ndjson output");
+    println!("\t-dry-run : with -sync, report the changes without applying them");
+    println!("\t-schema-only : compare the columns, types, nullability, lengths and keys of the tables without comparing rows");
+    println!("\t-config=<run.toml> : read settings from a toml file, flags on the command line override the file");
+    println!("\t-save-config=<run.toml> : write the settings used for this run to a toml file");
+    println!("\t-db1=<database> : compare every table of this mysql database against the tables of -db2 with the same name");
+    println!("\t-db2=<database> : second mysql database to compare every table of");
+    println!("\t-include=<pattern,pattern> : with -db1 and -db2, only compare the tables matching these glob patterns");
+    println!("\t-exclude=<pattern,pattern> : with -db1 and -db2, leave out the tables matching these glob patterns");
+    println!("\t-db-report=<report.json> : write the database level report of a -db1/-db2 comparison to a json file");
+    println!("\t-concurrent : run the [[job]] tables or the tables of a database comparison at the same time instead of one after another");
+    println!("\t-output=<output_file> : specify the name of the output file, the extension picks the format (.csv, .json, .ndjson, .html, .sql, .parquet)");
+    println!("\t-junit=<report.xml> : write a junit xml report where the comparison fails when the tables differ");
+    println!("\t-csv-layout=<layout> : split writes a file per kind of difference, unified writes one file with a _change column, side by side values and <missing> on the side a row is missing from (split, unified)");
+    println!("\t-csv-delimiter=<delimiter> : single character written between csv fields, or tab (default ,)");
+    println!("\t-csv-quote=<style> : when csv fields are quoted (necessary, always, never, non-numeric)");
+    println!("\t-csv-null=<text> : text written for null values in csv output (default empty)");
+    println!("\t-csv-terminator=<ending> : line ending of csv output (crlf, lf, cr)");
+    println!("\t-sql-dialect=<dialect> : dialect of the .sql patch script that makes table 2 match table 1 (mysql, sqlite)");
+    println!("\t-sql-transaction : wrap the .sql patch script in a transaction");
+    println!("Every setting can also be set with a DCT_ env var, e.g. DCT_OUTPUT=diff.csv or DCT_LOG_LEVEL=debug.");
+    println!("Env vars override the config file and flags on the command line override both.");
+    println!("Exit codes: 0 when the tables are identical, 1 when differences are found, 2 on error");
+}
+
+/// parse a comma separated list of table name glob patterns
+fn parse_table_patterns(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty())
+        .collect()
+}
+
+/// name of the output file of a job when the output file is shared by every
+/// job, which is the shared name with the job name in front of it
+pub(crate) fn job_output_file_name(job_name: &str, output_file_name: &str) -> String {
+    let output_path = Path::new(output_file_name);
+    let file_name = output_path
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or(output_file_name);
+    output_path
+        .with_file_name(format!("{}_{}", job_name, file_name))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// sqlite database file of a job, each job gets its own so that jobs running
+/// at the same time don't lock each other out or overwrite each other's tables
+pub(crate) fn job_sqlite_file_name(job_name: &str) -> String {
+    format!("db_{}.sqlite3", sanitize_name(job_name))
+}
+
+/// parse the value of the csv delimiter flag, which is either a single byte
+/// character or the word tab
+fn parse_csv_delimiter(value: &str) -> u8 {
+    match value {
+        "tab" | "\\t" => b'\t',
+        value if value.len() == 1 => value.as_bytes()[0],
+        &_ => {
+            panic!("csv delimiter must be a single character: {}", value);
+        }
+    }
+}
+
+/// split a table argument into the name of the table to stage the data in and
+/// the source implied by the value. A path ending in `.csv` is read as a csv
+/// file and staged in a table named after the file, and a value prefixed with
+/// `mysql:` or `postgres:` is read as a `schema.table` reference in that
+/// database. A `path.sqlite:table` reference is compared in place inside of the
+/// file, and anything else is the name of a mysql table
+fn parse_table_argument(value: &str) -> (String, SourceType) {
+    if let Some((file_path, table_name)) = value.rsplit_once(':') {
+        if [".sqlite", ".sqlite3", ".db"].iter().any(|extension| file_path.ends_with(extension)) {
+            return (
+                sanitize_name(table_name),
+                SourceType::Sqlite(file_path.to_string(), table_name.to_string())
+            );
+        }
+    }
+
+    if let Some(table_reference) = value.strip_prefix("mysql:") {
+        return (
+            sanitize_name(table_reference),
+            SourceType::MySqlTable(table_reference.to_string())
+        );
+    }
+
+    if let Some(table_reference) = value.strip_prefix("postgres:") {
+        return (
+            sanitize_name(table_reference),
+            SourceType::Postgres(table_reference.to_string())
+        );
+    }
+
+    if value.ends_with(".csv") {
+        let file_stem = Path::new(value)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("csv");
+        return (sanitize_name(file_stem), SourceType::Csv(value.to_string()));
+    }
+    (sanitize_name(value), SourceType::MySqlTable(value.to_string()))
+}
+
+/// parse a comma separated list of `column_1:column_2` pairs, panicking on a
+/// pair without a `:`
+pub(crate) fn parse_column_mapping(value: &str) -> Vec<(String, String)> {
+    value
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| match pair.split_once(':') {
+            Some((column_1, column_2)) => (column_1.trim().to_string(), column_2.trim().to_string()),
+            None => {
+                panic!("column mapping {} should be in the form column_1:column_2", pair);
+            }
+        })
+        .collect()
 }
 
 impl Default for Arguments {
@@ -88,7 +342,17 @@ impl Default for Arguments {
 }
 
 impl Arguments {
+    /// parse the arguments passed in on the command line
     pub fn new () -> Arguments {
+        Arguments::from_args(std::env::args().skip(1).collect())
+    }
+
+    /// parse a list of flags. Settings from `-config` files are applied first,
+    /// then the settings from `DCT_` env vars and then the rest of the flags,
+    /// so env vars override the file and the command line overrides both.
+    /// `-save-config` writes the settings that were used back out, without
+    /// the connection urls, so the run can be reproduced
+    pub fn from_args(cli_args: Vec<String>) -> Arguments {
         // init argument struct
         let current_date_stamp = Local::now().format("%Y%m%d%H%M%S").to_string();
 
@@ -105,25 +369,75 @@ impl Arguments {
             number_of_rows_to_generate: 0,
             table_name_1: format!("table_1{}", current_date_stamp),
             table_name_2: format!("table_2{}", current_date_stamp),
+            source_type_1: SourceType::MySql,
+            source_type_2: SourceType::MySql,
+            connection_url_1: "".to_string(),
+            connection_url_2: "".to_string(),
+            key_columns: Vec::new(),
+            column_mapping: Vec::new(),
             create_sqlite_comparison_files: true,
             in_memory_sqlite: false,
             auto_yes: false,
+            sync: false,
+            dry_run: false,
+            schema_only: false,
             output_file_name: "".to_string(),
             output_file_type: OutputFileType::Csv,
-            log_output: LogOutput::Console
+            junit_file_name: "".to_string(),
+            csv_layout: CsvLayout::Split,
+            csv_options: CsvOptions::default(),
+            sql_dialect: SqlDialect::MySql,
+            sql_transaction: false,
+            log_output: LogOutput::Console,
+            log_verbosity: LogVerbosity::Info,
+            job_name: "".to_string(),
+            sqlite_file_name: DEFAULT_SQLITE_FILE_NAME.to_string(),
+            jobs: Vec::new(),
+            concurrent_jobs: false,
+            database_1: "".to_string(),
+            database_2: "".to_string(),
+            include_tables: Vec::new(),
+            exclude_tables: Vec::new(),
+            database_report_file_name: "".to_string(),
         };
 
-        if std::env::args().len() == 1 {
+        // settings from env vars go between the config file and the command line
+        let env_args = Config::from_env().to_flags();
+        if cli_args.is_empty() && env_args.is_empty() {
             println!("No args passed in, running with default args");
             return return_arguments;
         }
 
+        // pull out the config flags and put the settings of any config file
+        // ahead of the command line flags
+        let mut config_args = Vec::new();
+        let mut command_line_args = Vec::new();
+        let mut job_configs = Vec::new();
+        let mut save_config_path = None;
+        for arg in cli_args {
+            match arg.split_once('=') {
+                Some(("-config", config_path)) => {
+                    println!("config file: {}", config_path);
+                    let mut config = Config::load(config_path);
+                    job_configs.extend(config.jobs.take().unwrap_or_default());
+                    config_args.extend(config.to_flags());
+                }
+                Some(("-save-config", config_path)) => {
+                    save_config_path = Some(config_path.to_string());
+                }
+                _ => command_line_args.push(arg),
+            }
+        }
+        let mut override_args = env_args;
+        override_args.extend(command_line_args);
+        let mut args = config_args.clone();
+        args.extend(override_args.iter().cloned());
+
         // loop over each argument
-        // first argument is the name of the program so we can ignore it
-        for arg in std::env::args().skip(1){
+        for arg in args.iter(){
             // if arg contains '=' split the flag + value and print
             if arg.contains('='){
-                let mut split_string = arg.split('=');
+                let mut split_string = arg.splitn(2, '=');
                 let flag = split_string.next();
                 let value = split_string.next();
                 // NOTE: I don't know why I'm setting the env var here
@@ -132,25 +446,61 @@ impl Arguments {
                 // live with?
                 std::env::set_var(flag.unwrap(), value.unwrap());
                 match flag.unwrap() {
-                    "-q" => {
+                    "-q" | "-q1" => {
                         return_arguments.mysql_query_1= value.unwrap().to_string();
                         println!("query: {}", return_arguments.mysql_query_1);
                     }
+                    "-q2" => {
+                        return_arguments.mysql_query_2= value.unwrap().to_string();
+                        println!("query 2: {}", return_arguments.mysql_query_2);
+                    }
                     "-gen" => {
                         return_arguments.generate_data = true;
                         let number_of_rows = value.unwrap().parse::<i32>().unwrap();
                         return_arguments.number_of_rows_to_generate = number_of_rows;
                     }
                     "-t1" => {
-                        let table_name = value.unwrap();
+                        let (table_name, source_type) = parse_table_argument(value.unwrap());
                         return_arguments.table_name_1 = format!("{}{}",table_name, current_date_stamp);
+                        return_arguments.source_type_1 = source_type;
                         println!("table name 1: {}", return_arguments.table_name_1);
                     }
                     "-t2" => {
-                        let table_name = value.unwrap();
+                        let (table_name, source_type) = parse_table_argument(value.unwrap());
                         return_arguments.table_name_2 = format!("{}{}",table_name, current_date_stamp);
+                        return_arguments.source_type_2 = source_type;
                         println!("table name 2: {}", return_arguments.table_name_2);
                     }
+                    "-url1" => {
+                        return_arguments.connection_url_1 = value.unwrap().to_string();
+                    }
+                    "-url2" => {
+                        return_arguments.connection_url_2 = value.unwrap().to_string();
+                    }
+                    "-log-level" => {
+                        return_arguments.log_verbosity = match value.unwrap() {
+                            "debug" => LogVerbosity::Debug,
+                            "info" => LogVerbosity::Info,
+                            "warning" | "warn" => LogVerbosity::Warning,
+                            "error" => LogVerbosity::Error,
+                            &_ => {
+                                panic!("Unknown log level: {}", value.unwrap());
+                            }
+                        };
+                    }
+                    "-key" => {
+                        return_arguments.key_columns = value
+                            .unwrap()
+                            .split(',')
+                            .map(|column| column.trim().to_string())
+                            .filter(|column| !column.is_empty())
+                            .collect();
+                        println!("key columns: {:?}", return_arguments.key_columns);
+                    }
+                    "-map" => {
+                        return_arguments.column_mapping = parse_column_mapping(value.unwrap());
+                        println!("column mapping: {:?}", return_arguments.column_mapping);
+                    }
                     "-logType" => {
                         match value.unwrap() {
                             "stdout" | "so" => {
@@ -170,6 +520,75 @@ impl Arguments {
                             }
                         }
                     }
+                    "-junit" => {
+                        return_arguments.junit_file_name = value.unwrap().to_string();
+                        println!("junit report: {}", return_arguments.junit_file_name);
+                    }
+                    "-csv-layout" => {
+                        return_arguments.csv_layout = match value.unwrap() {
+                            "split" => CsvLayout::Split,
+                            "unified" => CsvLayout::Unified,
+                            &_ => {
+                                panic!("Unknown csv layout: {}", value.unwrap());
+                            }
+                        };
+                    }
+                    "-csv-delimiter" => {
+                        return_arguments.csv_options.delimiter = parse_csv_delimiter(value.unwrap());
+                    }
+                    "-csv-quote" => {
+                        return_arguments.csv_options.quote_style = match value.unwrap() {
+                            "necessary" => ::csv::QuoteStyle::Necessary,
+                            "always" => ::csv::QuoteStyle::Always,
+                            "never" => ::csv::QuoteStyle::Never,
+                            "non-numeric" => ::csv::QuoteStyle::NonNumeric,
+                            &_ => {
+                                panic!("Unknown csv quote style: {}", value.unwrap());
+                            }
+                        };
+                    }
+                    "-csv-null" => {
+                        return_arguments.csv_options.null_value = value.unwrap().to_string();
+                    }
+                    "-csv-terminator" => {
+                        return_arguments.csv_options.terminator = match value.unwrap() {
+                            "crlf" => ::csv::Terminator::CRLF,
+                            "lf" => ::csv::Terminator::Any(b'\n'),
+                            "cr" => ::csv::Terminator::Any(b'\r'),
+                            &_ => {
+                                panic!("Unknown csv line terminator: {}", value.unwrap());
+                            }
+                        };
+                    }
+                    "-db1" => {
+                        return_arguments.database_1 = value.unwrap().to_string();
+                        println!("database 1: {}", return_arguments.database_1);
+                    }
+                    "-db2" => {
+                        return_arguments.database_2 = value.unwrap().to_string();
+                        println!("database 2: {}", return_arguments.database_2);
+                    }
+                    "-include" => {
+                        return_arguments.include_tables = parse_table_patterns(value.unwrap());
+                        println!("include tables: {:?}", return_arguments.include_tables);
+                    }
+                    "-exclude" => {
+                        return_arguments.exclude_tables = parse_table_patterns(value.unwrap());
+                        println!("exclude tables: {:?}", return_arguments.exclude_tables);
+                    }
+                    "-db-report" => {
+                        return_arguments.database_report_file_name = value.unwrap().to_string();
+                        println!("database report: {}", return_arguments.database_report_file_name);
+                    }
+                    "-sql-dialect" => {
+                        return_arguments.sql_dialect = match value.unwrap() {
+                            "mysql" => SqlDialect::MySql,
+                            "sqlite" => SqlDialect::Sqlite,
+                            &_ => {
+                                panic!("Unknown sql dialect: {}", value.unwrap());
+                            }
+                        };
+                    }
                     "-output" => {
                         println!("output file: {}", value.unwrap());
                         return_arguments.output_file_name = value.unwrap().to_string();
@@ -183,6 +602,18 @@ impl Arguments {
                             "json" => {
                                 return_arguments.output_file_type = OutputFileType::Json;
                             }
+                            "ndjson" => {
+                                return_arguments.output_file_type = OutputFileType::Ndjson;
+                            }
+                            "html" => {
+                                return_arguments.output_file_type = OutputFileType::Html;
+                            }
+                            "sql" => {
+                                return_arguments.output_file_type = OutputFileType::Sql;
+                            }
+                            "parquet" => {
+                                return_arguments.output_file_type = OutputFileType::Parquet;
+                            }
                             _ => {
                                 println!("Unknown file type: {}", file_type);
                             }
@@ -215,6 +646,7 @@ impl Arguments {
                     }
                     "-verbose" => {
                         return_arguments.verbose = true;
+                        return_arguments.log_verbosity = LogVerbosity::Debug;
                         println!("verbose output enabled");
                     }
                     "-c" => {
@@ -233,10 +665,45 @@ impl Arguments {
                         return_arguments.create_sqlite_comparison_files = true;
                         println!("creating sqlite comparison files while in flight");
                     }
+                    "-sql-transaction" => {
+                        return_arguments.sql_transaction = true;
+                        println!("wrapping sql patch script in a transaction");
+                    }
+                    "-sync" => {
+                        return_arguments.sync = true;
+                        println!("syncing table 2 to match table 1");
+                    }
+                    "-dry-run" => {
+                        return_arguments.dry_run = true;
+                        println!("dry run, no changes will be applied");
+                    }
+                    "-schema-only" => {
+                        return_arguments.schema_only = true;
+                        println!("comparing schemas only");
+                    }
+                    "-concurrent" => {
+                        return_arguments.concurrent_jobs = true;
+                        println!("running jobs concurrently");
+                    }
                     "-auto-yes" => {
                         return_arguments.auto_yes = true;
                         println!("auto yes enabled");
                     }
+                    // turn off a switch that is on by default or was turned on
+                    // by the config file or an env var
+                    "-no-c" => return_arguments.clean = false,
+                    "-no-in-memory" => return_arguments.in_memory_sqlite = false,
+                    "-no-create-in-flight" => return_arguments.create_sqlite_comparison_files = false,
+                    "-no-sql-transaction" => return_arguments.sql_transaction = false,
+                    "-no-sync" => return_arguments.sync = false,
+                    "-no-dry-run" => return_arguments.dry_run = false,
+                    "-no-schema-only" => return_arguments.schema_only = false,
+                    "-no-concurrent" => return_arguments.concurrent_jobs = false,
+                    "-no-auto-yes" => return_arguments.auto_yes = false,
+                    "-no-verbose" => {
+                        return_arguments.verbose = false;
+                        return_arguments.log_verbosity = LogVerbosity::Info;
+                    }
                     &_ => {
                         println!("Unknown argument:{}",arg);
                     }
@@ -244,6 +711,65 @@ impl Arguments {
             }
         }
 
+        // csv files are loaded whole, there is nothing to run a query against
+        for (query, source_type) in [
+            (&return_arguments.mysql_query_1, &return_arguments.source_type_1),
+            (&return_arguments.mysql_query_2, &return_arguments.source_type_2),
+        ] {
+            if let (false, SourceType::Csv(file_path)) = (query.is_empty(), source_type) {
+                panic!("queries can't be run against csv files, remove the query for {}", file_path);
+            }
+        }
+
+        // ndjson output streams the changed rows to the file instead of keeping
+        // them, which would leave sync with nothing to apply
+        if return_arguments.sync && matches!(return_arguments.output_file_type, OutputFileType::Ndjson) {
+            panic!("-sync can't be combined with .ndjson output, the changed rows are streamed to the file");
+        }
+
+        // each job starts from the settings of the config file, overrides them
+        // with its own and is then overridden by the env vars and command line
+        let output_file_name = Config::from_flags(&args).output;
+        let override_output_file_name = Config::from_flags(&override_args).output;
+        for (i, job) in job_configs.iter().enumerate() {
+            let job_name = job.name.clone().unwrap_or(format!("job_{}", i + 1));
+            println!("job: {}", job_name);
+            let mut job_args = config_args.clone();
+            job_args.extend(job.to_flags());
+            job_args.extend(override_args.iter().cloned());
+
+            // an output file shared by every job, either from the env vars, the
+            // command line or the top of the config file, is split up per job
+            let shared_output_file_name = match job.output {
+                Some(_) => override_output_file_name.as_ref(),
+                None => output_file_name.as_ref(),
+            };
+            if let Some(shared_output_file_name) = shared_output_file_name {
+                job_args.push(format!("-output={}", job_output_file_name(&job_name, shared_output_file_name)));
+            }
+
+            let mut job_arguments = Arguments::from_args(job_args);
+            job_arguments.sqlite_file_name = job_sqlite_file_name(&job_name);
+            job_arguments.job_name = job_name;
+            // the junit report covers every job and the sqlite files are shared
+            // by every job, so both are handled once for all of them
+            job_arguments.junit_file_name = "".to_string();
+            job_arguments.clean = false;
+            return_arguments.jobs.push(job_arguments);
+        }
+
+        if let Some(save_config_path) = save_config_path {
+            let mut config = Config::from_flags(&args);
+            // connection urls can hold credentials so they aren't written out
+            config.url_1 = None;
+            config.url_2 = None;
+            if !job_configs.is_empty() {
+                config.jobs = Some(job_configs);
+            }
+            config.save(&save_config_path);
+            println!("saved config to {}", save_config_path);
+        }
+
         // finally retured the parsed arugments
         return_arguments
     }
@@ -0,0 +1,336 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::collections::BTreeMap;
+use crate::interface::argument_parser::parse_column_mapping;
+
+/// Settings for a comparison run read from or saved to a toml file, or read
+/// from `DCT_` env vars. Every setting matches one of the command line flags
+/// and takes the same values, e.g. `table_1 = "left.csv"` and
+/// `DCT_LEFT_TABLE=left.csv` are the same as passing `-t1=left.csv`. Env vars
+/// override the config file and command line flags override both
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// name of a job, used in the summary and to keep the output of each job
+    /// apart
+    pub name: Option<String>,
+    /// -t1
+    pub table_1: Option<String>,
+    /// -t2
+    pub table_2: Option<String>,
+    /// -url1
+    pub url_1: Option<String>,
+    /// -url2
+    pub url_2: Option<String>,
+    /// -q1
+    pub query_1: Option<String>,
+    /// -q2
+    pub query_2: Option<String>,
+    /// -key
+    pub key: Option<Vec<String>>,
+    /// -map, as table 1 column = table 2 column
+    pub map: Option<BTreeMap<String, String>>,
+    /// -output
+    pub output: Option<String>,
+    /// -csv-layout
+    pub csv_layout: Option<String>,
+    /// -csv-delimiter
+    pub csv_delimiter: Option<String>,
+    /// -csv-quote
+    pub csv_quote: Option<String>,
+    /// -csv-null
+    pub csv_null: Option<String>,
+    /// -csv-terminator
+    pub csv_terminator: Option<String>,
+    /// -sql-dialect
+    pub sql_dialect: Option<String>,
+    /// -sql-transaction
+    pub sql_transaction: Option<bool>,
+    /// -junit
+    pub junit: Option<String>,
+    /// -sync
+    pub sync: Option<bool>,
+    /// -dry-run
+    pub dry_run: Option<bool>,
+    /// -schema-only
+    pub schema_only: Option<bool>,
+    /// -gen
+    pub generate_rows: Option<i32>,
+    /// -logType
+    pub log_type: Option<String>,
+    /// -log-level
+    pub log_level: Option<String>,
+    /// -verbose
+    pub verbose: Option<bool>,
+    /// -c
+    pub clean: Option<bool>,
+    /// -in-memory
+    pub in_memory: Option<bool>,
+    /// -create-in-flight
+    pub create_in_flight: Option<bool>,
+    /// -auto-yes
+    pub auto_yes: Option<bool>,
+    /// -concurrent
+    pub concurrent: Option<bool>,
+    /// -db1
+    pub database_1: Option<String>,
+    /// -db2
+    pub database_2: Option<String>,
+    /// -include
+    pub include: Option<Vec<String>>,
+    /// -exclude
+    pub exclude: Option<Vec<String>>,
+    /// -db-report
+    pub database_report: Option<String>,
+    /// comparisons to run as part of one job, written as `[[job]]` tables.
+    /// Each job takes the settings above and overrides them with its own
+    #[serde(rename = "job")]
+    pub jobs: Option<Vec<Config>>,
+}
+
+impl Config {
+    /// read a config from a toml file, panicking if it can't be read or has
+    /// settings that aren't known
+    pub fn load(file_path: &str) -> Config {
+        let contents = match std::fs::read_to_string(file_path) {
+            Ok(contents) => contents,
+            Err(error) => {
+                panic!("unable to read config file {}: {}", file_path, error);
+            }
+        };
+        match toml::from_str(&contents) {
+            Ok(config) => config,
+            Err(error) => {
+                panic!("unable to parse config file {}: {}", file_path, error);
+            }
+        }
+    }
+
+    /// write the config out as a toml file
+    pub fn save(&self, file_path: &str) {
+        let contents = match toml::to_string_pretty(self) {
+            Ok(contents) => contents,
+            Err(error) => {
+                panic!("unable to serialize config: {}", error);
+            }
+        };
+        if let Err(error) = std::fs::write(file_path, contents) {
+            panic!("unable to write config file {}: {}", file_path, error);
+        }
+    }
+
+    /// read the settings from the `DCT_` env vars of the process
+    pub fn from_env() -> Config {
+        Config::from_env_vars(std::env::vars())
+    }
+
+    /// read the settings from a list of env vars, ignoring any var without
+    /// the `DCT_` prefix and warning about a `DCT_` var that isn't a setting
+    /// before ignoring it too. Switches are turned on with true, 1 or yes and
+    /// off with false, 0 or no
+    pub fn from_env_vars<I: IntoIterator<Item = (String, String)>>(vars: I) -> Config {
+        let mut flags = Vec::new();
+        for (name, value) in vars {
+            if !name.starts_with("DCT_") {
+                continue;
+            }
+            let flag = match name.as_str() {
+                "DCT_LEFT_TABLE" => "-t1",
+                "DCT_RIGHT_TABLE" => "-t2",
+                "DCT_LEFT_URL" => "-url1",
+                "DCT_RIGHT_URL" => "-url2",
+                "DCT_LEFT_QUERY" => "-q1",
+                "DCT_RIGHT_QUERY" => "-q2",
+                "DCT_LEFT_DATABASE" => "-db1",
+                "DCT_RIGHT_DATABASE" => "-db2",
+                "DCT_KEY" => "-key",
+                "DCT_MAP" => "-map",
+                "DCT_OUTPUT" => "-output",
+                "DCT_CSV_LAYOUT" => "-csv-layout",
+                "DCT_CSV_DELIMITER" => "-csv-delimiter",
+                "DCT_CSV_QUOTE" => "-csv-quote",
+                "DCT_CSV_NULL" => "-csv-null",
+                "DCT_CSV_TERMINATOR" => "-csv-terminator",
+                "DCT_SQL_DIALECT" => "-sql-dialect",
+                "DCT_JUNIT" => "-junit",
+                "DCT_GEN" => "-gen",
+                "DCT_LOG_TYPE" => "-logType",
+                "DCT_LOG_LEVEL" => "-log-level",
+                "DCT_INCLUDE" => "-include",
+                "DCT_EXCLUDE" => "-exclude",
+                "DCT_DB_REPORT" => "-db-report",
+                &_ => {
+                    let switch = match name.as_str() {
+                        "DCT_SQL_TRANSACTION" => "-sql-transaction",
+                        "DCT_SYNC" => "-sync",
+                        "DCT_DRY_RUN" => "-dry-run",
+                        "DCT_SCHEMA_ONLY" => "-schema-only",
+                        "DCT_VERBOSE" => "-verbose",
+                        "DCT_CLEAN" => "-c",
+                        "DCT_IN_MEMORY" => "-in-memory",
+                        "DCT_CREATE_IN_FLIGHT" => "-create-in-flight",
+                        "DCT_AUTO_YES" => "-auto-yes",
+                        "DCT_CONCURRENT" => "-concurrent",
+                        &_ => {
+                            // the log isn't set up yet since it is configured
+                            // by these settings, so the warning is printed
+                            eprintln!("warning: ignoring unknown setting env var {}", name);
+                            continue;
+                        }
+                    };
+                    match value.to_lowercase().as_str() {
+                        "true" | "1" | "yes" => flags.push(switch.to_string()),
+                        "false" | "0" | "no" => flags.push(format!("-no-{}", &switch[1..])),
+                        "" => {}
+                        &_ => {
+                            panic!("{} should be true or false, got {}", name, value);
+                        }
+                    }
+                    continue;
+                }
+            };
+            flags.push(format!("{}={}", flag, value));
+        }
+        Config::from_flags(&flags)
+    }
+
+    /// collect the settings of the flags passed in, later flags overriding
+    /// earlier ones. Flags that aren't settings are ignored
+    pub fn from_flags(flags: &[String]) -> Config {
+        let mut config = Config::default();
+        for flag in flags.iter() {
+            match flag.split_once('=') {
+                Some((name, value)) => {
+                    let value = value.to_string();
+                    match name {
+                        "-t1" => config.table_1 = Some(value),
+                        "-t2" => config.table_2 = Some(value),
+                        "-url1" => config.url_1 = Some(value),
+                        "-url2" => config.url_2 = Some(value),
+                        "-q" | "-q1" => config.query_1 = Some(value),
+                        "-q2" => config.query_2 = Some(value),
+                        "-key" => {
+                            config.key = Some(value.split(',').map(|column| column.trim().to_string()).collect());
+                        }
+                        "-map" => config.map = Some(parse_column_mapping(&value).into_iter().collect()),
+                        "-output" => config.output = Some(value),
+                        "-csv-layout" => config.csv_layout = Some(value),
+                        "-csv-delimiter" => config.csv_delimiter = Some(value),
+                        "-csv-quote" => config.csv_quote = Some(value),
+                        "-csv-null" => config.csv_null = Some(value),
+                        "-csv-terminator" => config.csv_terminator = Some(value),
+                        "-sql-dialect" => config.sql_dialect = Some(value),
+                        "-junit" => config.junit = Some(value),
+                        "-gen" => config.generate_rows = value.parse().ok(),
+                        "-logType" => config.log_type = Some(value),
+                        "-log-level" => config.log_level = Some(value),
+                        "-db1" => config.database_1 = Some(value),
+                        "-db2" => config.database_2 = Some(value),
+                        "-include" => {
+                            config.include = Some(value.split(',').map(|pattern| pattern.trim().to_string()).collect());
+                        }
+                        "-exclude" => {
+                            config.exclude = Some(value.split(',').map(|pattern| pattern.trim().to_string()).collect());
+                        }
+                        "-db-report" => config.database_report = Some(value),
+                        &_ => {}
+                    }
+                }
+                None => match flag.as_str() {
+                    "-sql-transaction" => config.sql_transaction = Some(true),
+                    "-sync" => config.sync = Some(true),
+                    "-dry-run" => config.dry_run = Some(true),
+                    "-schema-only" => config.schema_only = Some(true),
+                    "-verbose" => config.verbose = Some(true),
+                    "-c" => config.clean = Some(true),
+                    "-in-memory" => config.in_memory = Some(true),
+                    "-create-in-flight" => config.create_in_flight = Some(true),
+                    "-auto-yes" => config.auto_yes = Some(true),
+                    "-concurrent" => config.concurrent = Some(true),
+                    "-no-sql-transaction" => config.sql_transaction = Some(false),
+                    "-no-sync" => config.sync = Some(false),
+                    "-no-dry-run" => config.dry_run = Some(false),
+                    "-no-schema-only" => config.schema_only = Some(false),
+                    "-no-verbose" => config.verbose = Some(false),
+                    "-no-c" => config.clean = Some(false),
+                    "-no-in-memory" => config.in_memory = Some(false),
+                    "-no-create-in-flight" => config.create_in_flight = Some(false),
+                    "-no-auto-yes" => config.auto_yes = Some(false),
+                    "-no-concurrent" => config.concurrent = Some(false),
+                    &_ => {}
+                },
+            }
+        }
+        config
+    }
+
+    /// turn the config back into the command line flags it stands for.
+    /// Switches set to false turn into their `-no-` flag so they override a
+    /// default or an earlier setting, the name and jobs have no flag so are
+    /// left out
+    pub fn to_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        let values = [
+            ("-t1", self.table_1.clone()),
+            ("-t2", self.table_2.clone()),
+            ("-url1", self.url_1.clone()),
+            ("-url2", self.url_2.clone()),
+            ("-q1", self.query_1.clone()),
+            ("-q2", self.query_2.clone()),
+            ("-key", self.key.as_ref().map(|key| key.join(","))),
+            (
+                "-map",
+                self.map.as_ref().map(|map| {
+                    map.iter()
+                        .map(|(column_1, column_2)| format!("{}:{}", column_1, column_2))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                }),
+            ),
+            ("-output", self.output.clone()),
+            ("-csv-layout", self.csv_layout.clone()),
+            ("-csv-delimiter", self.csv_delimiter.clone()),
+            ("-csv-quote", self.csv_quote.clone()),
+            ("-csv-null", self.csv_null.clone()),
+            ("-csv-terminator", self.csv_terminator.clone()),
+            ("-sql-dialect", self.sql_dialect.clone()),
+            ("-junit", self.junit.clone()),
+            ("-gen", self.generate_rows.map(|generate_rows| generate_rows.to_string())),
+            ("-logType", self.log_type.clone()),
+            ("-log-level", self.log_level.clone()),
+            ("-db1", self.database_1.clone()),
+            ("-db2", self.database_2.clone()),
+            ("-include", self.include.as_ref().map(|include| include.join(","))),
+            ("-exclude", self.exclude.as_ref().map(|exclude| exclude.join(","))),
+            ("-db-report", self.database_report.clone()),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value {
+                flags.push(format!("{}={}", name, value));
+            }
+        }
+
+        let switches = [
+            ("-sql-transaction", self.sql_transaction),
+            ("-sync", self.sync),
+            ("-dry-run", self.dry_run),
+            ("-schema-only", self.schema_only),
+            ("-verbose", self.verbose),
+            ("-c", self.clean),
+            ("-in-memory", self.in_memory),
+            ("-create-in-flight", self.create_in_flight),
+            ("-auto-yes", self.auto_yes),
+            ("-concurrent", self.concurrent),
+        ];
+        for (name, switch) in switches {
+            match switch {
+                Some(true) => flags.push(name.to_string()),
+                Some(false) => flags.push(format!("-no-{}", &name[1..])),
+                None => {}
+            }
+        }
+        flags
+    }
+}
@@ -34,10 +34,16 @@ pub struct Log {
 
 impl Log {
     pub fn new(args:&Arguments) -> Log {
+        Log::with_settings(args.log_output.clone(), args.log_verbosity.clone())
+    }
+
+    /// creates a log that writes to the given output, skipping messages below
+    /// the given verbosity
+    pub fn with_settings(log_type: LogOutput, verbose: LogVerbosity) -> Log {
         Log {
             log_file_name: create_log_file().unwrap(),
-            log_type: args.log_output.clone(),
-            verbose: LogVerbosity::Info
+            log_type,
+            verbose
         }
     }
 
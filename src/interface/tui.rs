@@ -20,14 +20,14 @@ use ratatui::{
     },
     Frame,
 };
-use std::{io, io::Stdout};
+use std::{io, io::Stdout, sync::{Arc, Mutex}};
 
 /// State management for the UI
 /// I'm aware this may not be the best way to do this
 /// but as a wise sage once said "I'm just a girl trying to do her best"
 static mut CURRENT_STATE: UIState = UIState::StartUp;
 static mut PREVIOUS_STATE: UIState = UIState::StartUp;
-static mut COMPARISON_DATA: Option<ComparisonData> = None;
+static COMPARISON_DATA: Mutex<Option<Arc<ComparisonData>>> = Mutex::new(None);
 
 pub fn set_state(state: UIState, log: &Log) {
     // set the current state as the previous state and set the current state
@@ -60,28 +60,13 @@ pub fn get_prev_state() -> UIState {
 }
 
 pub fn set_comparison_data(data: ComparisonData) {
-    unsafe {
-        COMPARISON_DATA = Some(data);
-    }
+    *COMPARISON_DATA.lock().unwrap() = Some(Arc::new(data));
 }
 
-// TODO: the rust-analyzer suggests that we should use addr_or!()
-// here instead to create a raw pointer
-// doing that would require changing the signature of this
-// which then causes the fact that ComparisonData does not have the
-// clone or copy trait on the sqlx types
-// so for now we'll just ignore this for now
-pub fn get_comparison_data() -> Option<&'static ComparisonData> {
-    unsafe {
-        match &COMPARISON_DATA {
-            Some(data) => {
-                Some(data)
-            }
-            None => {
-                None
-            }
-        }
-    }
+/// returns a shared handle to the results of the last comparison that was
+/// run, so redrawing the results doesn't copy every row
+pub fn get_comparison_data() -> Option<Arc<ComparisonData>> {
+    COMPARISON_DATA.lock().unwrap().clone()
 }
 
 /// Handle the rendering of the terminal UI based on the current state
@@ -254,9 +239,10 @@ pub fn run_terminal(args: &argument_parser::Arguments, log: &Log) -> io::Result<
 fn draw_results(frame: &mut Frame) {
     // create widget data
     let comparison_data = get_comparison_data().unwrap();
-    let unique_table_1_rows_str = comparison_data.unique_table_1_rows.len().to_string();
-    let unique_table_2_rows_str = comparison_data.unique_table_2_rows.len().to_string();
-    let changed_rows_str = comparison_data.changed_rows.len().to_string();
+    let unique_table_1_rows_str = comparison_data.unique_table_1_row_count().to_string();
+    let unique_table_2_rows_str = comparison_data.unique_table_2_row_count().to_string();
+    let changed_rows_str = comparison_data.changed_row_count().to_string();
+    let unchanged_rows_str = comparison_data.unchanged_row_count.to_string();
 
     // initialize the rows of the table
     let rows = [
@@ -264,6 +250,7 @@ fn draw_results(frame: &mut Frame) {
         Row::new(vec!["Unique Table 1 rows", &unique_table_1_rows_str]),
         Row::new(vec!["Unique Table 2 rows", &unique_table_2_rows_str]),
         Row::new(vec!["Changed rows", &changed_rows_str]),
+        Row::new(vec!["Unchanged rows", &unchanged_rows_str]),
         Row::new(vec!["Press [q] to exit"]),
         Row::new(vec!["Press [m] to return to the main menu"]),
     ];
@@ -278,7 +265,39 @@ fn draw_results(frame: &mut Frame) {
         Table::new(rows, widths)
         .block(Block::default());
 
-    frame.render_widget(table_widget, frame.area());
+    // split the screen between the summary and the changed column details
+    let [summary_area, changes_area] =
+        Layout::vertical([Constraint::Length(7), Constraint::Min(0)]).areas(frame.area());
+    frame.render_widget(table_widget, summary_area);
+
+    // list each differing column of the changed rows, as many as fit on screen
+    let change_rows: Vec<Row> = comparison_data
+        .changed_rows
+        .iter()
+        .flat_map(|changed_row| {
+            changed_row.differences.iter().map(move |difference| {
+                Row::new(vec![
+                    changed_row.key_string(),
+                    difference.column.clone(),
+                    difference.left_value.to_string(),
+                    difference.right_value.to_string(),
+                ])
+            })
+        })
+        .take(changes_area.height as usize)
+        .collect();
+
+    let change_widths = [
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+        Constraint::Percentage(25),
+    ];
+    let changes_widget = Table::new(change_rows, change_widths)
+        .header(Row::new(vec!["Key", "Column", "Table 1 value", "Table 2 value"]).bold())
+        .block(Block::bordered().title("Changed columns"));
+
+    frame.render_widget(changes_widget, changes_area);
 }
 
 /// Calculate the layout of the UI elements.
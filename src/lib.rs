@@ -1,13 +1,28 @@
 pub mod datastore {
+    pub mod data_source;
     pub mod mysql;
+    pub mod postgres;
     pub mod sqlite;
     pub mod csv;
+    pub mod json;
+    pub mod ndjson;
+    pub mod html;
+    pub mod sql_patch;
+    pub mod parquet;
+    pub mod junit;
     pub mod generator;
     pub mod transformer;
 }
 
 pub mod models {
     pub mod comparison_data;
+    pub mod database_comparison;
+    pub mod changed_row;
+    pub mod diff_record;
+    pub mod result_row;
+    pub mod schema_diff;
+    pub mod value;
+    pub mod column_mapping;
     pub mod table_data;
 }
 
@@ -16,6 +31,7 @@ pub mod interface {
     pub mod tui;
     pub mod state;
     pub mod log_options;
+    pub mod config;
     pub mod log;
 }
 
@@ -1,4 +1,7 @@
-use std::io;
+use std::{
+    panic,
+    process::ExitCode,
+};
 use data_comparison_tool::{
     processor,
     interface::{
@@ -8,26 +11,54 @@ use data_comparison_tool::{
     interface::log::Log
 };
 
-fn main() -> Result<(), io::Error> {
-    // parse input arguments and initialize the log
-    let args = argument_parser::Arguments::new();
-    let log = Log::new(&args);
+/// exit code when the tables are identical
+const EXIT_IDENTICAL: u8 = 0;
+/// exit code when differences are found between the tables
+const EXIT_DIFFERENCES: u8 = 1;
+/// exit code when the comparison could not be run
+const EXIT_ERROR: u8 = 2;
 
-    // if help flag passed in don't do anything else
-    if args.help {
-        return Ok(());
-    }
+fn main() -> ExitCode {
+    // errors surface as panics, which have already been printed by the time
+    // they are caught here, so only the exit code is left to pick. Bad
+    // arguments panic too so parsing happens inside of the catch as well
+    let exit_code = panic::catch_unwind(|| {
+        // parse input arguments and initialize the log
+        let args = argument_parser::Arguments::new();
+        let log = Log::new(&args);
 
-    // if the TUI flag is passed in run the terminal and early return
-    if args.tui {
-        let result = tui::run_terminal(&args, &log);
-        ratatui::restore();
-        return result;
-    }
+        // if help flag passed in don't do anything else
+        if args.help {
+            return EXIT_IDENTICAL;
+        }
 
-    //TODO: Eventually need to come back and
-    // maybe do something with this
-    let _comparison_data = processor::run_comparison(&args, &log);
-    Ok(())
-}
+        // if the TUI flag is passed in run the terminal and early return
+        if args.tui {
+            let result = tui::run_terminal(&args, &log);
+            ratatui::restore();
+            return match result {
+                Ok(_) => EXIT_IDENTICAL,
+                Err(error) => {
+                    eprintln!("terminal error: {}", error);
+                    EXIT_ERROR
+                }
+            };
+        }
 
+        let has_differences = if !args.database_1.is_empty() || !args.database_2.is_empty() {
+            processor::run_database_comparison(&args, &log).has_differences()
+        } else if !args.jobs.is_empty() {
+            processor::run_jobs(&args, &log)
+                .iter()
+                .any(|comparison_data| comparison_data.has_differences())
+        } else {
+            processor::run_comparison(&args, &log).has_differences()
+        };
+        if has_differences {
+            EXIT_DIFFERENCES
+        } else {
+            EXIT_IDENTICAL
+        }
+    });
+    ExitCode::from(exit_code.unwrap_or(EXIT_ERROR))
+}
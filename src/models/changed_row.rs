@@ -0,0 +1,81 @@
+use crate::models::value::Value;
+use serde::{
+    ser::SerializeMap,
+    Serialize,
+    Serializer,
+};
+
+/// A single column that differs between the two tables for a matched row
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ColumnDifference {
+    /// name of the column in the first table
+    pub column: String,
+    /// value of the column in the first table
+    pub left_value: Value,
+    /// value of the mapped column in the second table
+    pub right_value: Value,
+}
+
+/// A row that exists in both tables but has at least one compared column
+/// that differs
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangedRow {
+    /// name and value of each key column the two rows were matched on
+    pub key_values: Vec<(String, Value)>,
+    /// every compared column that differs between the two rows
+    pub differences: Vec<ColumnDifference>,
+    /// name and value of every compared column that is the same in both rows,
+    /// used by outputs that show the whole row
+    pub unchanged_values: Vec<(String, Value)>,
+}
+
+impl ChangedRow {
+    pub fn new(key_values: Vec<(String, Value)>, differences: Vec<ColumnDifference>) -> ChangedRow {
+        ChangedRow {
+            key_values,
+            differences,
+            unchanged_values: Vec::new(),
+        }
+    }
+
+    /// value of a compared column that is the same in both rows
+    pub fn unchanged_value(&self, column: &str) -> Option<&Value> {
+        self.unchanged_values
+            .iter()
+            .find(|(unchanged_column, _)| unchanged_column == column)
+            .map(|(_, value)| value)
+    }
+
+    /// format the key of the row for display, e.g. `tenant_id=1, sku=abc`
+    pub fn key_string(&self) -> String {
+        self.key_values
+            .iter()
+            .map(|(column, value)| format!("{}={}", column, value))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+/// changed rows serialize as `{"key": {"id": 1}, "differences": [...]}`
+impl Serialize for ChangedRow {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let key: Vec<(&String, &Value)> = self.key_values.iter().map(|(column, value)| (column, value)).collect();
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("key", &KeyMap(&key))?;
+        map.serialize_entry("differences", &self.differences)?;
+        map.end()
+    }
+}
+
+/// helper to serialize a list of column/value pairs as a json object
+pub(crate) struct KeyMap<'a>(pub &'a [(&'a String, &'a Value)]);
+
+impl Serialize for KeyMap<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (column, value) in self.0 {
+            map.serialize_entry(column, value)?;
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,105 @@
+use crate::models::table_data::TableData;
+use serde::Serialize;
+
+/// A column from the first table and the column in the second table it is
+/// compared against
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ColumnPair {
+    /// name of the column in the first table
+    pub column_1: String,
+    /// name of the column in the second table
+    pub column_2: String,
+}
+
+/// Struct to hold which non key columns of the two tables are compared against
+/// each other, and which columns have no partner on the other side
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ColumnMapping {
+    /// pairs of columns that are compared
+    pub pairs: Vec<ColumnPair>,
+    /// columns in the first table that have no column to compare against
+    pub unmapped_table_1_columns: Vec<String>,
+    /// columns in the second table that have no column to compare against
+    pub unmapped_table_2_columns: Vec<String>,
+    /// key columns of the first table under the names they are mapped to in
+    /// the second table, which is their own name when they aren't mapped
+    pub mapped_key: Vec<String>,
+}
+
+impl ColumnMapping {
+    /// pair up the columns of the two tables. Columns named in the custom
+    /// mapping are paired with their mapped column, every other column is
+    /// paired with the column of the same name in the second table. Key columns
+    /// are left out since they are compared by the join
+    pub fn new(
+        table_data_1: &TableData,
+        table_data_2: &TableData,
+        custom_mapping: &[(String, String)],
+    ) -> ColumnMapping {
+        // make sure every mapped column actually exists
+        for (column_1, column_2) in custom_mapping {
+            if !table_data_1.columns.iter().any(|column| &column.name == column_1) {
+                panic!("mapped column {} not found in table {}", column_1, table_data_1.table_name);
+            }
+            if !table_data_2.columns.iter().any(|column| &column.name == column_2) {
+                panic!("mapped column {} not found in table {}", column_2, table_data_2.table_name);
+            }
+        }
+
+        let mut pairs = Vec::new();
+        let mut unmapped_table_1_columns = Vec::new();
+        for column in table_data_1.columns.iter() {
+            if table_data_1.primary_key.contains(&column.name) {
+                continue;
+            }
+
+            let mapped_column = custom_mapping
+                .iter()
+                .find(|(column_1, _)| column_1 == &column.name)
+                .map(|(_, column_2)| column_2.clone())
+                .or_else(|| {
+                    table_data_2
+                        .columns
+                        .iter()
+                        .find(|column_2| column_2.name == column.name)
+                        .map(|column_2| column_2.name.clone())
+                });
+
+            match mapped_column {
+                Some(column_2) => pairs.push(ColumnPair {
+                    column_1: column.name.clone(),
+                    column_2,
+                }),
+                None => unmapped_table_1_columns.push(column.name.clone()),
+            }
+        }
+
+        // anything in the second table that wasn't paired up has no partner
+        let unmapped_table_2_columns = table_data_2
+            .columns
+            .iter()
+            .filter(|column| !table_data_2.primary_key.contains(&column.name))
+            .filter(|column| !pairs.iter().any(|pair| pair.column_2 == column.name))
+            .map(|column| column.name.clone())
+            .collect();
+
+        let mapped_key = table_data_1
+            .primary_key
+            .iter()
+            .map(|key| {
+                custom_mapping
+                    .iter()
+                    .find(|(column_1, _)| column_1 == key)
+                    .map(|(_, column_2)| column_2.clone())
+                    .unwrap_or_else(|| key.clone())
+            })
+            .collect();
+
+        ColumnMapping {
+            pairs,
+            unmapped_table_1_columns,
+            unmapped_table_2_columns,
+            mapped_key,
+        }
+    }
+}
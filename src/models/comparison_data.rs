@@ -1,29 +1,103 @@
+use crate::models::{
+    changed_row::ChangedRow,
+    column_mapping::ColumnMapping,
+    diff_record::StreamedCounts,
+    result_row::ResultRow,
+    schema_diff::SchemaDiff,
+    table_data::TableData,
+};
+
 /// Struct to hold the comparison data between the two tables
+#[derive(Clone, Debug)]
 pub struct ComparisonData {
+    /// Name, columns and key of the first table
+    pub table_data_1: TableData,
+
+    /// Name, columns and key of the second table
+    pub table_data_2: TableData,
+
+    /// Columns of the two tables that were compared against each other
+    pub column_mapping: ColumnMapping,
+
+    /// Differences between the schemas of the two tables
+    pub schema_diff: SchemaDiff,
+
     /// Rows that are unique to the first table and do not exist in the second
-    pub unique_table_1_rows: Vec<sqlx::sqlite::SqliteRow>,
+    pub unique_table_1_rows: Vec<ResultRow>,
 
     /// Rows that are unique to the second table and do not exist in the first
     /// table
-    pub unique_table_2_rows: Vec<sqlx::sqlite::SqliteRow>,
+    pub unique_table_2_rows: Vec<ResultRow>,
+
+    /// Rows that have the same primary key but differ in other columns, along
+    /// with the values of each column that differs
+    pub changed_rows: Vec<ChangedRow>,
+
+    /// Number of rows that have the same primary key and the same values in
+    /// every compared column
+    pub unchanged_row_count: usize,
+
+    /// Number of differing rows of each kind that were streamed straight to
+    /// the output file instead of being kept in the row lists above
+    pub streamed_counts: StreamedCounts,
+
+    /// Columns in the first table that have no column in the second table
+    /// to be compared against
+    pub unmapped_table_1_columns: Vec<String>,
 
-    /// Rows that have the same primary key but differ in other columns
-    pub changed_rows: Vec<sqlx::sqlite::SqliteRow>,
+    /// Columns in the second table that have no column in the first table
+    /// to be compared against
+    pub unmapped_table_2_columns: Vec<String>,
 }
 
 impl ComparisonData {
     /// Constructor for the comparison data struct
     pub fn new(
-        unique_table_1_data: Vec<sqlx::sqlite::SqliteRow>,
-        unique_table_2_data: Vec<sqlx::sqlite::SqliteRow>,
-        changed_rows_data: Vec<sqlx::sqlite::SqliteRow>,
+        unique_table_1_data: Vec<ResultRow>,
+        unique_table_2_data: Vec<ResultRow>,
+        changed_rows_data: Vec<ChangedRow>,
     ) -> ComparisonData {
         // return the new comparison object
         ComparisonData {
+            table_data_1: TableData::default(),
+            table_data_2: TableData::default(),
+            column_mapping: ColumnMapping::default(),
+            schema_diff: SchemaDiff::default(),
             unique_table_1_rows: unique_table_1_data,
             unique_table_2_rows: unique_table_2_data,
             changed_rows: changed_rows_data,
+            unchanged_row_count: 0,
+            streamed_counts: StreamedCounts::default(),
+            unmapped_table_1_columns: Vec::new(),
+            unmapped_table_2_columns: Vec::new(),
         }
     }
-}
 
+    /// whether the schemas of the tables differ or any row only exists in one
+    /// table or differs between them
+    pub fn has_differences(&self) -> bool {
+        !self.schema_diff.is_empty()
+            || !self.unique_table_1_rows.is_empty()
+            || !self.unique_table_2_rows.is_empty()
+            || !self.changed_rows.is_empty()
+            || self.streamed_counts.total() > 0
+    }
+
+    /// number of rows only in the first table, whether they were kept or
+    /// streamed to the output file
+    pub fn unique_table_1_row_count(&self) -> usize {
+        self.unique_table_1_rows.len() + self.streamed_counts.only_left
+    }
+
+    /// number of rows only in the second table, whether they were kept or
+    /// streamed to the output file
+    pub fn unique_table_2_row_count(&self) -> usize {
+        self.unique_table_2_rows.len() + self.streamed_counts.only_right
+    }
+
+    /// number of changed rows, whether they were kept or streamed to the
+    /// output file
+    pub fn changed_row_count(&self) -> usize {
+        self.changed_rows.len() + self.streamed_counts.changed
+    }
+}
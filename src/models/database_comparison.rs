@@ -0,0 +1,141 @@
+use crate::models::comparison_data::ComparisonData;
+use glob::Pattern;
+use serde::Serialize;
+
+/// Tables of two databases paired up by name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TablePairing {
+    /// tables found in both databases, compared against each other
+    pub paired_tables: Vec<String>,
+    /// tables that are only in the first database
+    pub only_in_database_1: Vec<String>,
+    /// tables that are only in the second database
+    pub only_in_database_2: Vec<String>,
+}
+
+impl TablePairing {
+    /// pair the tables of both databases by name, leaving out any table that
+    /// doesn't match one of the include patterns (when there are any) or that
+    /// matches one of the exclude patterns
+    pub fn new(
+        tables_1: &[String],
+        tables_2: &[String],
+        include_patterns: &[String],
+        exclude_patterns: &[String],
+    ) -> TablePairing {
+        let include_patterns = compile_patterns(include_patterns);
+        let exclude_patterns = compile_patterns(exclude_patterns);
+        let is_selected = |table_name: &&String| {
+            (include_patterns.is_empty() || include_patterns.iter().any(|pattern| pattern.matches(table_name)))
+                && !exclude_patterns.iter().any(|pattern| pattern.matches(table_name))
+        };
+
+        let mut pairing = TablePairing::default();
+        for table_name in tables_1.iter().filter(is_selected) {
+            if tables_2.contains(table_name) {
+                pairing.paired_tables.push(table_name.clone());
+            } else {
+                pairing.only_in_database_1.push(table_name.clone());
+            }
+        }
+        for table_name in tables_2.iter().filter(is_selected) {
+            if !tables_1.contains(table_name) {
+                pairing.only_in_database_2.push(table_name.clone());
+            }
+        }
+        pairing
+    }
+}
+
+/// parse the glob patterns used to pick tables, panicking on a bad pattern
+fn compile_patterns(patterns: &[String]) -> Vec<Pattern> {
+    patterns
+        .iter()
+        .map(|pattern| match Pattern::new(pattern) {
+            Ok(pattern) => pattern,
+            Err(error) => {
+                panic!("invalid table pattern {}: {}", pattern, error);
+            }
+        })
+        .collect()
+}
+
+/// Outcome of comparing one pair of tables
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableStatus {
+    Identical,
+    Different,
+    Failed,
+}
+
+/// Row counts of the comparison of one pair of tables
+#[derive(Clone, Debug, Serialize)]
+pub struct TableSummary {
+    pub table_name: String,
+    pub status: TableStatus,
+    pub only_left_count: usize,
+    pub only_right_count: usize,
+    pub changed_count: usize,
+    pub unchanged_count: usize,
+}
+
+impl TableSummary {
+    /// summarize the comparison of a table, None when the comparison failed
+    pub fn new(table_name: &str, result: Option<&ComparisonData>) -> TableSummary {
+        match result {
+            Some(result) => TableSummary {
+                table_name: table_name.to_string(),
+                status: if result.has_differences() {
+                    TableStatus::Different
+                } else {
+                    TableStatus::Identical
+                },
+                only_left_count: result.unique_table_1_row_count(),
+                only_right_count: result.unique_table_2_row_count(),
+                changed_count: result.changed_row_count(),
+                unchanged_count: result.unchanged_row_count,
+            },
+            None => TableSummary {
+                table_name: table_name.to_string(),
+                status: TableStatus::Failed,
+                only_left_count: 0,
+                only_right_count: 0,
+                changed_count: 0,
+                unchanged_count: 0,
+            },
+        }
+    }
+}
+
+/// Database level report of the comparison of every table in two databases
+#[derive(Clone, Debug, Serialize)]
+pub struct DatabaseComparison {
+    pub database_1: String,
+    pub database_2: String,
+    /// tables that are only in the first database
+    pub only_in_database_1: Vec<String>,
+    /// tables that are only in the second database
+    pub only_in_database_2: Vec<String>,
+    /// result of comparing each of the tables found in both databases
+    pub tables: Vec<TableSummary>,
+}
+
+impl DatabaseComparison {
+    /// whether a table is missing from one of the databases or any pair of
+    /// tables differs
+    pub fn has_differences(&self) -> bool {
+        !self.only_in_database_1.is_empty()
+            || !self.only_in_database_2.is_empty()
+            || self.tables.iter().any(|table| table.status == TableStatus::Different)
+    }
+
+    /// names of the tables that couldn't be compared
+    pub fn failed_tables(&self) -> Vec<&str> {
+        self.tables
+            .iter()
+            .filter(|table| table.status == TableStatus::Failed)
+            .map(|table| table.table_name.as_str())
+            .collect()
+    }
+}
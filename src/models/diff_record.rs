@@ -0,0 +1,44 @@
+use crate::models::{
+    changed_row::ChangedRow,
+    result_row::ResultRow,
+};
+use serde::Serialize;
+
+/// A single difference between the two tables, handed out one at a time while
+/// the comparison results are read so they can be written out without holding
+/// every row in memory
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DiffRecord {
+    /// row that only exists in the first table
+    OnlyLeft { row: ResultRow },
+    /// row that only exists in the second table
+    OnlyRight { row: ResultRow },
+    /// row that exists in both tables with at least one differing column
+    Changed(ChangedRow),
+}
+
+/// Number of records of each kind that were streamed to the output file
+/// instead of being kept in memory
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StreamedCounts {
+    pub only_left: usize,
+    pub only_right: usize,
+    pub changed: usize,
+}
+
+impl StreamedCounts {
+    /// count a record that was written out
+    pub fn add(&mut self, record: &DiffRecord) {
+        match record {
+            DiffRecord::OnlyLeft { .. } => self.only_left += 1,
+            DiffRecord::OnlyRight { .. } => self.only_right += 1,
+            DiffRecord::Changed(_) => self.changed += 1,
+        }
+    }
+
+    /// number of records of every kind
+    pub fn total(&self) -> usize {
+        self.only_left + self.only_right + self.changed
+    }
+}
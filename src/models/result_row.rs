@@ -0,0 +1,42 @@
+use crate::models::{
+    changed_row::KeyMap,
+    value::Value,
+};
+use serde::{
+    Serialize,
+    Serializer,
+};
+
+/// An owned row of a comparison result along with the names of its columns
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResultRow {
+    /// names of the columns in the row
+    pub columns: Vec<String>,
+    /// value of each column, in the same order as the column names
+    pub values: Vec<Value>,
+}
+
+impl ResultRow {
+    pub fn new(columns: Vec<String>, values: Vec<Value>) -> ResultRow {
+        ResultRow {
+            columns,
+            values,
+        }
+    }
+
+    /// look up the value of a column by name
+    pub fn get(&self, column: &str) -> Option<&Value> {
+        self.columns
+            .iter()
+            .position(|name| name == column)
+            .map(|index| &self.values[index])
+    }
+}
+
+/// result rows serialize as an object of column name to value
+impl Serialize for ResultRow {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let pairs: Vec<(&String, &Value)> = self.columns.iter().zip(self.values.iter()).collect();
+        KeyMap(&pairs).serialize(serializer)
+    }
+}
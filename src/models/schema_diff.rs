@@ -0,0 +1,200 @@
+use crate::models::{
+    column_mapping::ColumnMapping,
+    table_data::{
+        ColumnData,
+        TableData,
+    },
+};
+use serde::Serialize;
+
+/// What differs between the schemas of the two tables
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaDifferenceKind {
+    /// a column of the first table has no column to compare against
+    #[serde(rename = "only_in_table_1")]
+    OnlyInTable1,
+    /// a column of the second table has no column to compare against
+    #[serde(rename = "only_in_table_2")]
+    OnlyInTable2,
+    /// compared columns have different types
+    Type,
+    /// one of the compared columns accepts nulls and the other doesn't
+    Nullability,
+    /// compared columns hold a different number of characters
+    Length,
+    /// the tables are joined on differently named key columns
+    Key,
+}
+
+/// A single difference between the schemas of the two tables, with the value
+/// on each side of it
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SchemaDifference {
+    pub kind: SchemaDifferenceKind,
+    /// column of the first table, empty when the difference isn't about one
+    pub column_1: String,
+    /// column of the second table, empty when the difference isn't about one
+    pub column_2: String,
+    pub table_1_value: String,
+    pub table_2_value: String,
+}
+
+impl SchemaDifference {
+    /// describe the difference in a sentence naming both tables
+    pub fn describe(&self, table_1: &str, table_2: &str) -> String {
+        match self.kind {
+            SchemaDifferenceKind::OnlyInTable1 => format!("column {} only exists in {}", self.column_1, table_1),
+            SchemaDifferenceKind::OnlyInTable2 => format!("column {} only exists in {}", self.column_2, table_2),
+            SchemaDifferenceKind::Type | SchemaDifferenceKind::Nullability => format!(
+                "column {} is {} in {} and {} is {} in {}",
+                self.column_1, self.table_1_value, table_1, self.column_2, self.table_2_value, table_2
+            ),
+            SchemaDifferenceKind::Length => format!(
+                "column {} holds {} characters in {} and {} holds {} in {}",
+                self.column_1, self.table_1_value, table_1, self.column_2, self.table_2_value, table_2
+            ),
+            SchemaDifferenceKind::Key => format!(
+                "key columns differ: {} ({}) and {} ({})",
+                table_1, self.table_1_value, table_2, self.table_2_value
+            ),
+        }
+    }
+}
+
+/// Differences between the schemas of the two tables, found before any rows
+/// are compared
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SchemaDiff {
+    pub differences: Vec<SchemaDifference>,
+}
+
+impl SchemaDiff {
+    /// compare the key, the unpaired columns and the type, nullability and
+    /// length of every pair of compared columns. Key columns are compared
+    /// after the column mapping, types by their family so the same type named
+    /// by different sources matches. Nullability and length are only compared
+    /// when both sources report them
+    pub fn new(table_data_1: &TableData, table_data_2: &TableData, column_mapping: &ColumnMapping) -> SchemaDiff {
+        let mut differences = Vec::new();
+        if column_mapping.mapped_key != table_data_2.primary_key {
+            differences.push(SchemaDifference {
+                kind: SchemaDifferenceKind::Key,
+                column_1: "".to_string(),
+                column_2: "".to_string(),
+                table_1_value: table_data_1.primary_key.join(", "),
+                table_2_value: table_data_2.primary_key.join(", "),
+            });
+        }
+        for column in column_mapping.unmapped_table_1_columns.iter() {
+            differences.push(SchemaDifference {
+                kind: SchemaDifferenceKind::OnlyInTable1,
+                column_1: column.clone(),
+                column_2: "".to_string(),
+                table_1_value: table_data_1.source_type_of(column),
+                table_2_value: "".to_string(),
+            });
+        }
+        for column in column_mapping.unmapped_table_2_columns.iter() {
+            differences.push(SchemaDifference {
+                kind: SchemaDifferenceKind::OnlyInTable2,
+                column_1: "".to_string(),
+                column_2: column.clone(),
+                table_1_value: "".to_string(),
+                table_2_value: table_data_2.source_type_of(column),
+            });
+        }
+
+        for pair in column_mapping.pairs.iter() {
+            let (column_1, column_2) = match (
+                ColumnData::find(&table_data_1.columns, &pair.column_1),
+                ColumnData::find(&table_data_2.columns, &pair.column_2),
+            ) {
+                (Some(column_1), Some(column_2)) => (column_1, column_2),
+                _ => continue,
+            };
+            let difference = |kind: SchemaDifferenceKind, table_1_value: String, table_2_value: String| {
+                SchemaDifference {
+                    kind,
+                    column_1: column_1.name.clone(),
+                    column_2: column_2.name.clone(),
+                    table_1_value,
+                    table_2_value,
+                }
+            };
+
+            // lengths are compared on their own, so varchar(20) and
+            // varchar(40) only differ in length
+            if type_family(&column_1.data_type) != type_family(&column_2.data_type) {
+                differences.push(difference(
+                    SchemaDifferenceKind::Type,
+                    column_1.data_type.clone(),
+                    column_2.data_type.clone(),
+                ));
+            }
+            if let (Some(nullable_1), Some(nullable_2)) = (column_1.nullable, column_2.nullable) {
+                if nullable_1 != nullable_2 {
+                    differences.push(difference(
+                        SchemaDifferenceKind::Nullability,
+                        nullability_name(nullable_1),
+                        nullability_name(nullable_2),
+                    ));
+                }
+            }
+            if let (Some(max_length_1), Some(max_length_2)) = (column_1.max_length, column_2.max_length) {
+                if max_length_1 != max_length_2 {
+                    differences.push(difference(
+                        SchemaDifferenceKind::Length,
+                        max_length_1.to_string(),
+                        max_length_2.to_string(),
+                    ));
+                }
+            }
+        }
+
+        SchemaDiff { differences }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// family of a type regardless of the source that named it, e.g. integer for
+/// INT from mysql, bigint from postgres and INTEGER from sqlite. The families
+/// follow the sqlite type affinity rules with dates and times kept apart, any
+/// other type is its own family
+fn type_family(data_type: &str) -> String {
+    // the first word without the length, e.g. double for double precision
+    // and varchar for varchar(20)
+    let base_type = data_type
+        .split('(')
+        .next()
+        .unwrap_or(data_type)
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let family = match base_type.as_str() {
+        "timestamp" | "timestamptz" | "datetime" => "datetime",
+        "date" => "date",
+        "time" | "timetz" => "time",
+        "bool" | "boolean" | "serial" | "smallserial" | "bigserial" => "integer",
+        "numeric" | "decimal" | "dec" => "decimal",
+        "bytea" | "binary" | "varbinary" => "binary",
+        _ if base_type.contains("int") => "integer",
+        _ if base_type.contains("char") || base_type.contains("clob") || base_type.contains("text") => "text",
+        _ if base_type.contains("blob") => "binary",
+        _ if base_type.contains("real") || base_type.contains("floa") || base_type.contains("doub") => "real",
+        _ => return base_type,
+    };
+    family.to_string()
+}
+
+fn nullability_name(nullable: bool) -> String {
+    if nullable {
+        "nullable".to_string()
+    } else {
+        "not null".to_string()
+    }
+}
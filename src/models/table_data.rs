@@ -1,21 +1,114 @@
-use sqlx::mysql::MySqlColumn;
+use serde::Serialize;
+
+/// Struct to hold the properties of a single column independent of the
+/// source it was read from
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ColumnData {
+    /// name of the column
+    pub name: String,
+    /// type of the column as reported by the source
+    pub data_type: String,
+    /// whether the column accepts nulls, None when the source doesn't say
+    pub nullable: Option<bool>,
+    /// maximum number of characters the column holds, None when the source
+    /// doesn't say or the column has no length
+    pub max_length: Option<i64>,
+}
+
+impl ColumnData {
+    pub fn new(name: String, data_type: String) -> ColumnData {
+        ColumnData {
+            name,
+            data_type,
+            nullable: None,
+            max_length: None,
+        }
+    }
+
+    /// find a column of the table by name
+    pub fn find<'a>(columns: &'a [ColumnData], column_name: &str) -> Option<&'a ColumnData> {
+        columns.iter().find(|column| column.name == column_name)
+    }
+}
+
+/// A sqlite database file that is attached to the comparison connection so a
+/// table can be compared in place instead of being copied into staging
+#[derive(Clone, Debug, Serialize)]
+pub struct AttachedDatabase {
+    /// path to the sqlite database file
+    pub file_path: String,
+    /// schema name the file is attached under
+    pub alias: String,
+    /// name of the table inside of the attached file
+    pub table_name: String,
+}
+
+/// sqlite database file tables are staged and compared in unless a run picks
+/// another one
+pub const DEFAULT_SQLITE_FILE_NAME: &str = "db.sqlite3";
 
 /// Struct to hold the table properties to pass over to the sqlite querier
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct TableData {
     /// name of the table you're querying
     pub table_name: String,
+    /// schema of the table in its source, empty when the source has no
+    /// schemas or the table is in the database that is connected to
+    pub source_schema: String,
+    /// name of the table in its source, e.g. the mysql table or the table
+    /// inside of a sqlite file, as opposed to the name it is staged under
+    pub source_table: String,
     /// list of columns in the table
-    pub columns: Vec<MySqlColumn>,
-    /// primary key of the table we're joinin on
-    pub primary_key: String,
+    pub columns: Vec<ColumnData>,
+    /// key columns of the table we're joinin on, more than one column when the
+    /// table has a composite key
+    pub primary_key: Vec<String>,
+    /// database file the table is read from in place, if it was not staged
+    pub attached_database: Option<AttachedDatabase>,
+    /// sqlite database file the table is staged and compared in
+    pub sqlite_file_name: String,
 }
 
 impl TableData {
-    pub fn new(table_name: String, columns: Vec<MySqlColumn>, primary_key: String) -> TableData {
+    pub fn new(table_name: String, columns: Vec<ColumnData>, primary_key: Vec<String>) -> TableData {
         TableData {
+            source_schema: "".to_string(),
+            source_table: table_name.clone(),
             table_name,
             columns,
             primary_key,
+            attached_database: None,
+            sqlite_file_name: DEFAULT_SQLITE_FILE_NAME.to_string(),
+        }
+    }
+
+    /// type the named column has in the source table, empty when the column
+    /// is not part of the table
+    pub fn source_type_of(&self, column_name: &str) -> String {
+        self.columns
+            .iter()
+            .find(|column| column.name == column_name)
+            .map(|column| column.data_type.clone())
+            .unwrap_or_default()
+    }
+
+    /// name of the table in its source, as `schema.table` when it has a schema
+    pub fn source_name(&self) -> String {
+        if self.source_schema.is_empty() {
+            self.source_table.clone()
+        } else {
+            format!("{}.{}", self.source_schema, self.source_table)
+        }
+    }
+
+    /// name to select the rows of the table from. This is the table inside of
+    /// the attached database when there is one, otherwise the staging table
+    pub fn query_name(&self) -> String {
+        match &self.attached_database {
+            Some(attached_database) => {
+                format!("{}.{}", attached_database.alias, attached_database.table_name)
+            }
+            None => self.table_name.clone(),
         }
     }
 }
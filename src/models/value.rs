@@ -0,0 +1,98 @@
+use chrono::{
+    NaiveDate,
+    NaiveDateTime,
+    NaiveTime,
+};
+use serde::{
+    Serialize,
+    Serializer,
+};
+use std::fmt;
+
+/// An owned value read out of a comparison result, independent of the
+/// database engine it was read from
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDateTime),
+    /// decimals are kept as their text representation so no precision is
+    /// lost, which is also how they are staged in sqlite and written out by
+    /// the exporters that have no decimal type
+    Decimal(String),
+}
+
+impl Value {
+    /// interpret a text value using the type of the column it came from in
+    /// the source table, falling back to text when it doesn't parse
+    pub fn from_text(text: String, source_type: &str) -> Value {
+        let source_type = source_type.to_lowercase();
+        if source_type.contains("timestamp") || source_type.contains("datetime") {
+            ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(&text, format).ok())
+                .map(Value::DateTime)
+                .unwrap_or(Value::Text(text))
+        } else if source_type == "date" {
+            NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+                .map(Value::Date)
+                .unwrap_or(Value::Text(text))
+        } else if source_type.starts_with("time") {
+            NaiveTime::parse_from_str(&text, "%H:%M:%S%.f")
+                .map(Value::Time)
+                .unwrap_or(Value::Text(text))
+        } else if source_type.starts_with("numeric") || source_type.starts_with("decimal") {
+            Value::Decimal(text)
+        } else {
+            Value::Text(text)
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        *self == Value::Null
+    }
+
+    /// the value formatted as a string, None when the value is null
+    pub fn as_string(&self) -> Option<String> {
+        match self {
+            Value::Null => None,
+            value => Some(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "NULL"),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+            Value::Text(value) => write!(f, "{}", value),
+            Value::Bytes(value) => write!(f, "{}", String::from_utf8_lossy(value)),
+            Value::Date(value) => write!(f, "{}", value),
+            Value::Time(value) => write!(f, "{}", value),
+            Value::DateTime(value) => write!(f, "{}", value),
+            Value::Decimal(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// values serialize as plain json values, with dates, times and decimals
+/// written as strings
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Int(value) => serializer.serialize_i64(*value),
+            Value::Float(value) => serializer.serialize_f64(*value),
+            Value::Text(value) => serializer.serialize_str(value),
+            Value::Bytes(value) => serializer.serialize_str(&String::from_utf8_lossy(value)),
+            value => serializer.serialize_str(&value.to_string()),
+        }
+    }
+}
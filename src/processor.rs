@@ -1,21 +1,58 @@
 use async_std::task::block_on;
-use std::time::SystemTime;
+use std::{
+    any::Any,
+    panic,
+    thread,
+    time::SystemTime,
+};
 use crate::{
     datastore::{
+        data_source::DataSource,
         mysql,
+        mysql::MySqlSource,
+        postgres::PostgresSource,
+        csv::CsvSource,
         sqlite,
+        sqlite::SqliteSource,
         csv,
+        csv::sanitize_name,
+        json,
+        ndjson::NdjsonWriter,
+        html,
+        sql_patch,
+        sql_patch::PatchKind,
+        parquet,
+        junit,
+        junit::JunitTestCase,
         generator,
-        transformer
     },
-    models::comparison_data::ComparisonData,
+    models::{
+        column_mapping::ColumnMapping,
+        comparison_data::ComparisonData,
+        schema_diff::SchemaDiff,
+        database_comparison::{
+            DatabaseComparison,
+            TablePairing,
+            TableStatus,
+            TableSummary,
+        },
+        table_data::TableData,
+    },
     interface::{
         log::Log,
         argument_parser,
-        argument_parser::OutputFileType
+        argument_parser::{
+            CsvLayout,
+            OutputFileType,
+            SourceType,
+            SqlDialect,
+        },
     },
 };
 
+/// number of statements applied in each transaction when syncing
+const SYNC_BATCH_SIZE: usize = 500;
+
 pub fn run_comparison(args: &argument_parser::Arguments, log: &Log) -> ComparisonData {
     // if the generate data flag is set then generate the data
     // for the two tables passed in
@@ -24,10 +61,10 @@ pub fn run_comparison(args: &argument_parser::Arguments, log: &Log) -> Compariso
         log.info(&format!("Generated {} rows for each table", args.number_of_rows_to_generate));
     }
 
-    // if the clean flag is set then clean up the sqlite databses
+    // if the clean flag is set then clean up the sqlite databses, leaving any
+    // files that are being compared in place
     if args.clean {
-        block_on(sqlite::clear_sqlite_data());
-        log.info("cleaned sqlite database");
+        clean_sqlite_data(&[args], log);
     }
 
     // compare the table data
@@ -37,41 +74,375 @@ pub fn run_comparison(args: &argument_parser::Arguments, log: &Log) -> Compariso
         log.info(&format!("exporting data to file: {}", args.output_file_name));
         match args.output_file_type {
             OutputFileType::Csv => {
-                csv::export_comparison_data_to_csv(&result, &args.output_file_name, log);
+                let exported = match args.csv_layout {
+                    CsvLayout::Split => {
+                        csv::export_comparison_data_to_csv(&result, &args.output_file_name, &args.csv_options, log)
+                    }
+                    CsvLayout::Unified => {
+                        csv::export_comparison_data_to_unified_csv(&result, &args.output_file_name, &args.csv_options, log)
+                    }
+                };
+                if let Err(error) = exported {
+                    panic!("error occurred while writing csv output: {}", error);
+                }
             }
             OutputFileType::Json => {
-                panic!("JSON export not implemented yet");
+                json::export_comparison_data_to_json(&result, &args.output_file_name, log);
+            }
+            OutputFileType::Html => {
+                html::export_comparison_data_to_html(&result, &args.output_file_name, log);
+            }
+            OutputFileType::Sql => {
+                sql_patch::export_comparison_data_to_sql(
+                    &result,
+                    &args.output_file_name,
+                    &args.sql_dialect,
+                    args.sql_transaction,
+                    log
+                );
+            }
+            OutputFileType::Parquet => {
+                if let Err(error) = parquet::export_comparison_data_to_parquet(&result, &args.output_file_name, log) {
+                    panic!("error occurred while writing parquet output: {}", error);
+                }
+            }
+            OutputFileType::Ndjson => {
+                // the records were already written while the tables were compared
             }
         }
     }
 
+    if !args.junit_file_name.is_empty() {
+        junit::export_comparison_results_to_junit(&[JunitTestCase::from_result(&result)], &args.junit_file_name, log);
+    }
+
+    if args.sync && !args.schema_only {
+        sync_table_2(args, &result, log);
+    }
+
     result
 }
 
-fn compare_data(args: &argument_parser::Arguments, log: &Log) -> ComparisonData {
-    // extract mysql data ino the table data struct
-    let table_1_data = block_on(mysql::get_mysql_table_data(&args.table_name_1, log));
-    let table_2_data = block_on(mysql::get_mysql_table_data(&args.table_name_2, log));
+/// remove the sqlite files left behind by earlier runs, keeping any sqlite
+/// files that are compared by the runs passed in
+fn clean_sqlite_data(runs: &[&argument_parser::Arguments], log: &Log) {
+    let compared_files: Vec<&str> = runs
+        .iter()
+        .flat_map(|run| [&run.source_type_1, &run.source_type_2])
+        .filter_map(|source_type| match source_type {
+            SourceType::Sqlite(file_path, _) => Some(file_path.as_str()),
+            _ => None,
+        })
+        .collect();
+    block_on(sqlite::clear_sqlite_data(&compared_files));
+    log.info("cleaned sqlite database");
+}
 
-    // declare query_1 and query_2 variables but don't give them a value
-    let mut query_1 = args.mysql_query_1.clone();
-    let mut query_2 = args.mysql_query_2.clone();
+/// message a comparison panicked with, which is a String when the message was
+/// formatted and a &str when it wasn't
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown error".to_string(),
+        },
+    }
+}
 
-    if query_1.is_empty()  {
-        query_1 = format!("select * from {}", args.table_name_1);
+/// run the jobs passed in, one after another or all at once, logging the
+/// differences found by each of them. A failing job doesn't stop the others,
+/// its result is the message it failed with instead
+fn run_each_job(args: &argument_parser::Arguments, log: &Log) -> Vec<Result<ComparisonData, String>> {
+    // the jobs don't clean up after themselves so that one job can't remove
+    // the files of another, everything is cleaned once before they start
+    if args.clean {
+        clean_sqlite_data(&args.jobs.iter().collect::<Vec<_>>(), log);
     }
 
-    if query_2.is_empty()  {
-        query_2 = format!("select * from {}", args.table_name_2);
+    let outcomes: Vec<thread::Result<ComparisonData>> = if args.concurrent_jobs {
+        log.info(&format!("running {} jobs concurrently", args.jobs.len()));
+        thread::scope(|scope| {
+            let handles: Vec<_> = args
+                .jobs
+                .iter()
+                .map(|job| scope.spawn(move || run_comparison(job, log)))
+                .collect();
+            handles.into_iter().map(|handle| handle.join()).collect()
+        })
+    } else {
+        args.jobs
+            .iter()
+            .map(|job| {
+                log.info(&format!("running job {}", job.job_name));
+                panic::catch_unwind(panic::AssertUnwindSafe(|| run_comparison(job, log)))
+            })
+            .collect()
+    };
+
+    args.jobs
+        .iter()
+        .zip(outcomes)
+        .map(|(job, outcome)| match outcome {
+            Ok(result) => {
+                log.info(&format!(
+                    "job {}: {} rows only in table 1, {} rows only in table 2, {} changed rows, {} unchanged rows",
+                    job.job_name,
+                    result.unique_table_1_row_count(),
+                    result.unique_table_2_row_count(),
+                    result.changed_row_count(),
+                    result.unchanged_row_count
+                ));
+                Ok(result)
+            }
+            Err(payload) => {
+                let message = panic_message(payload);
+                log.error(&format!("job {} failed: {}", job.job_name, message));
+                Err(message)
+            }
+        })
+        .collect()
+}
+
+/// junit test cases of the jobs, named after each job so failed jobs are
+/// reported under the same name as the ones that ran
+fn job_test_cases<'a>(
+    jobs: &[argument_parser::Arguments],
+    outcomes: &'a [Result<ComparisonData, String>],
+) -> Vec<JunitTestCase<'a>> {
+    jobs.iter()
+        .zip(outcomes.iter())
+        .map(|(job, outcome)| JunitTestCase {
+            name: job.job_name.clone(),
+            outcome: outcome.as_ref().map_err(String::as_str),
+        })
+        .collect()
+}
+
+/// run every job of a config file and log a combined summary of the
+/// differences found. Once every job is done the run panics if any of them
+/// failed
+pub fn run_jobs(args: &argument_parser::Arguments, log: &Log) -> Vec<ComparisonData> {
+    let outcomes = run_each_job(args, log);
+    let failed_jobs: Vec<&str> = args
+        .jobs
+        .iter()
+        .zip(outcomes.iter())
+        .filter(|(_, outcome)| outcome.is_err())
+        .map(|(job, _)| job.job_name.as_str())
+        .collect();
+
+    let jobs_with_differences = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, Ok(result) if result.has_differences()))
+        .count();
+    log.info(&format!(
+        "{} jobs run: {} identical, {} with differences, {} failed",
+        args.jobs.len(),
+        args.jobs.len() - jobs_with_differences - failed_jobs.len(),
+        jobs_with_differences,
+        failed_jobs.len()
+    ));
+
+    if !args.junit_file_name.is_empty() {
+        junit::export_comparison_results_to_junit(&job_test_cases(&args.jobs, &outcomes), &args.junit_file_name, log);
     }
 
-    // generate the select statements + return the rows generated from the select statement
-    let database_name = "test";
-    let mysql_rows_1= block_on(mysql::query_mysql(&query_1,database_name, log));
-    let mysql_rows_2 = block_on(mysql::query_mysql(&query_2, database_name, log));
+    let results: Vec<ComparisonData> = outcomes.into_iter().flatten().collect();
+    if !failed_jobs.is_empty() {
+        panic!("jobs failed: {}", failed_jobs.join(", "));
+    }
+    results
+}
+
+/// compare every table of two mysql databases. The tables are paired up by
+/// name and each pair is run as a job, the tables only found in one of the
+/// databases are reported and a database level report is written when asked
+/// for. Once every pair is compared the run panics if any of them failed
+pub fn run_database_comparison(args: &argument_parser::Arguments, log: &Log) -> DatabaseComparison {
+    if args.database_1.is_empty() || args.database_2.is_empty() {
+        panic!("both -db1 and -db2 are needed to compare whole databases");
+    }
+
+    let tables_1 = block_on(mysql::list_tables(&args.connection_url_1, &args.database_1, log));
+    let tables_2 = block_on(mysql::list_tables(&args.connection_url_2, &args.database_2, log));
+    let pairing = TablePairing::new(&tables_1, &tables_2, &args.include_tables, &args.exclude_tables);
+    for table_name in pairing.only_in_database_1.iter() {
+        log.warn(&format!("table {} only exists in {}", table_name, args.database_1));
+    }
+    for table_name in pairing.only_in_database_2.iter() {
+        log.warn(&format!("table {} only exists in {}", table_name, args.database_2));
+    }
+
+    // every pair of tables is compared as a job with the rest of the settings
+    // passed in, staged under names that can't collide with the other pairs
+    let current_date_stamp = chrono::Local::now().format("%Y%m%d%H%M%S").to_string();
+    let mut database_args = args.clone();
+    database_args.jobs = pairing
+        .paired_tables
+        .iter()
+        .map(|table_name| {
+            let mut job = args.clone();
+            job.job_name = table_name.clone();
+            job.sqlite_file_name = argument_parser::job_sqlite_file_name(table_name);
+            job.table_name_1 = format!("{}_{}{}", sanitize_name(&args.database_1), sanitize_name(table_name), current_date_stamp);
+            job.table_name_2 = format!("{}_{}{}", sanitize_name(&args.database_2), sanitize_name(table_name), current_date_stamp);
+            job.source_type_1 = SourceType::MySqlTable(format!("{}.{}", args.database_1, table_name));
+            job.source_type_2 = SourceType::MySqlTable(format!("{}.{}", args.database_2, table_name));
+            job.mysql_query_1 = "".to_string();
+            job.mysql_query_2 = "".to_string();
+            if !args.output_file_name.is_empty() {
+                job.output_file_name = argument_parser::job_output_file_name(table_name, &args.output_file_name);
+            }
+            job.junit_file_name = "".to_string();
+            job.clean = false;
+            job
+        })
+        .collect();
+
+    let outcomes = run_each_job(&database_args, log);
+    let report = DatabaseComparison {
+        database_1: args.database_1.clone(),
+        database_2: args.database_2.clone(),
+        only_in_database_1: pairing.only_in_database_1,
+        only_in_database_2: pairing.only_in_database_2,
+        tables: pairing
+            .paired_tables
+            .iter()
+            .zip(outcomes.iter())
+            .map(|(table_name, outcome)| TableSummary::new(table_name, outcome.as_ref().ok()))
+            .collect(),
+    };
+
+    let different_tables = report.tables.iter().filter(|table| table.status == TableStatus::Different).count();
+    log.info(&format!(
+        "{} tables compared: {} identical, {} with differences, {} failed, {} only in {}, {} only in {}",
+        report.tables.len(),
+        report.tables.len() - different_tables - report.failed_tables().len(),
+        different_tables,
+        report.failed_tables().len(),
+        report.only_in_database_1.len(),
+        report.database_1,
+        report.only_in_database_2.len(),
+        report.database_2
+    ));
+
+    if !args.junit_file_name.is_empty() {
+        let test_cases = job_test_cases(&database_args.jobs, &outcomes);
+        junit::export_comparison_results_to_junit(&test_cases, &args.junit_file_name, log);
+    }
+    if !args.database_report_file_name.is_empty() {
+        json::export_database_comparison_to_json(&report, &args.database_report_file_name, log);
+    }
 
+    if !report.failed_tables().is_empty() {
+        panic!("tables failed to compare: {}", report.failed_tables().join(", "));
+    }
+    report
+}
+
+/// apply the statements that make table 2 match table 1 to the mysql
+/// database, asking before anything is changed unless auto yes is set
+fn sync_table_2(args: &argument_parser::Arguments, result: &ComparisonData, log: &Log) {
+    if !matches!(args.source_type_2, SourceType::MySql | SourceType::MySqlTable(_)) {
+        panic!("sync is only supported when table 2 is read from mysql");
+    }
+
+    let statements = sql_patch::patch_statements(result, &SqlDialect::MySql);
+    let count_of = |kind: PatchKind| statements.iter().filter(|statement| statement.kind == kind).count();
+    log.info(&format!(
+        "sync will insert {} rows, update {} rows and delete {} rows in {}",
+        count_of(PatchKind::Insert),
+        count_of(PatchKind::Update),
+        count_of(PatchKind::Delete),
+        result.table_data_2.source_name()
+    ));
+
+    if args.dry_run {
+        for statement in statements.iter() {
+            log.info(&statement.sql);
+        }
+        log.info("dry run, no changes applied");
+        return;
+    }
+    if statements.is_empty() {
+        log.info("tables already match, nothing to sync");
+        return;
+    }
+
+    if !args.auto_yes {
+        log.info(&format!("apply these changes to {}? (yes/no)", result.table_data_2.source_name()));
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input).unwrap();
+        if !matches!(input.trim(), "yes" | "y") {
+            log.info("sync cancelled, no changes applied");
+            return;
+        }
+    }
+
+    let sql: Vec<String> = statements.iter().map(|statement| statement.sql.clone()).collect();
+    let rows_affected = block_on(mysql::execute_in_batches(&sql, &args.connection_url_2, SYNC_BATCH_SIZE, log));
+    let applied_of = |kind: PatchKind| -> u64 {
+        statements
+            .iter()
+            .zip(rows_affected.iter())
+            .filter(|(statement, _)| statement.kind == kind)
+            .map(|(_, rows)| rows)
+            .sum()
+    };
+    log.info(&format!(
+        "sync applied: {} rows inserted, {} rows updated, {} rows deleted",
+        applied_of(PatchKind::Insert),
+        applied_of(PatchKind::Update),
+        applied_of(PatchKind::Delete)
+    ));
+}
+
+fn compare_data(args: &argument_parser::Arguments, log: &Log) -> ComparisonData {
+    // discover the schema of both sides before loading any rows so that a bad
+    // key override fails before any data is moved
+    let source_1 = Source::new(&args.source_type_1, &args.connection_url_1);
+    let source_2 = Source::new(&args.source_type_2, &args.connection_url_2);
+    let (staging_name_1, staging_name_2) = staging_table_names(args);
+    let mut table_1_data = source_1.get_table_data(&staging_name_1, &args.mysql_query_1, &args.key_columns, log);
+    let mut table_2_data = source_2.get_table_data(&staging_name_2, &args.mysql_query_2, &args.key_columns, log);
+    table_1_data.sqlite_file_name = args.sqlite_file_name.clone();
+    table_2_data.sqlite_file_name = args.sqlite_file_name.clone();
+    validate_key_columns(&table_1_data, log);
+    validate_key_columns(&table_2_data, log);
+
+    // pair up the columns to compare and report how the schemas differ before
+    // any rows are moved
+    let column_mapping = ColumnMapping::new(&table_1_data, &table_2_data, &args.column_mapping);
+    let schema_diff = SchemaDiff::new(&table_1_data, &table_2_data, &column_mapping);
+    for difference in schema_diff.differences.iter() {
+        log.warn(&format!("schema difference: {}", difference.describe("table 1", "table 2")));
+    }
+    log.info(&format!("schema differences: {}", schema_diff.differences.len()));
+
+    // ndjson output is written while the tables are compared, so the schema
+    // differences go to the file before any rows
+    let streaming = matches!(args.output_file_type, OutputFileType::Ndjson) && !args.output_file_name.is_empty();
+    if args.schema_only {
+        if streaming {
+            let mut writer = NdjsonWriter::new(&args.output_file_name);
+            writer.write_schema_differences(&schema_diff.differences);
+            writer.finish(log);
+        }
+        let mut result = ComparisonData::new(Vec::new(), Vec::new(), Vec::new());
+        result.table_data_1 = table_1_data;
+        result.table_data_2 = table_2_data;
+        result.unmapped_table_1_columns = column_mapping.unmapped_table_1_columns.clone();
+        result.unmapped_table_2_columns = column_mapping.unmapped_table_2_columns.clone();
+        result.column_mapping = column_mapping;
+        result.schema_diff = schema_diff;
+        return result;
+    }
+
+    // extract each side from its source and load it into sqlite, when no
+    // query is passed in the source selects the whole table
     let mut now = SystemTime::now();
-    block_on(transformer::mysql_table_to_sqlite_table(&mysql_rows_1, &table_1_data, log));
+    source_1.load_rows(&args.mysql_query_1, &table_1_data, log);
     match now.elapsed(){
         Ok(elapsed) => {
             let log_message = format!("Time it took to migrate data to sqlite for table 1: {}.{}", elapsed.as_secs(),elapsed.subsec_millis());
@@ -84,7 +455,7 @@ fn compare_data(args: &argument_parser::Arguments, log: &Log) -> ComparisonData
     }
 
     now = SystemTime::now();
-    block_on(transformer::mysql_table_to_sqlite_table(&mysql_rows_2, &table_2_data, log));
+    source_2.load_rows(&args.mysql_query_2, &table_2_data, log);
     match now.elapsed(){
         Ok(elapsed) => {
             let log_message = format!("Time it took to migrate data to sqlite for table 2: {}.{}", elapsed.as_secs(),elapsed.subsec_millis());
@@ -95,29 +466,184 @@ fn compare_data(args: &argument_parser::Arguments, log: &Log) -> ComparisonData
         }
     }
 
-    // compare the data
+    // compare the data, streaming the differences straight to the output file
+    // when writing ndjson so the rows are never all held in memory
+    let create_sqlite_comparison_files = confirm_sqlite_comparison_files(args, log);
     now = SystemTime::now();
-    let result =
-        block_on(
-            sqlite::compare_tables(
+    let mut result = if streaming {
+        log.info(&format!("streaming differences to file: {}", args.output_file_name));
+        let mut writer = NdjsonWriter::new(&args.output_file_name);
+        writer.write_schema_differences(&schema_diff.differences);
+        let mut result = block_on(
+            sqlite::stream_comparison(
                 &table_1_data,
                 &table_2_data,
-                args.create_sqlite_comparison_files,
-                args.in_memory_sqlite,
+                &column_mapping,
+                create_sqlite_comparison_files,
                 log,
-                args.auto_yes
+                |record| writer.write_record(&record)
             )
         );
+        result.streamed_counts = writer.finish(log);
+        result
+    } else {
+        block_on(
+            sqlite::compare_tables(
+                &table_1_data,
+                &table_2_data,
+                &column_mapping,
+                create_sqlite_comparison_files,
+                log
+            )
+        )
+    };
 
     match now.elapsed(){
         Ok(elapsed) => {
             log.info(&format!("Time it took to compare both tables: {}.{}", elapsed.as_secs(),elapsed.subsec_millis()));
-            log.info(&format!("rows in table 1 that are not in table 2: {}", result.unique_table_1_rows.len()));
-            log.info(&format!("rows in table 2 that are not in table 1: {}", result.unique_table_2_rows.len()));
-            log.info(&format!("rows that are different between the two tables: {}", result.changed_rows.len()));
+            if !streaming {
+                log.info(&format!("rows in table 1 that are not in table 2: {}", result.unique_table_1_rows.len()));
+                log.info(&format!("rows in table 2 that are not in table 1: {}", result.unique_table_2_rows.len()));
+                log.info(&format!("rows that are different between the two tables: {}", result.changed_rows.len()));
+            }
+            log.info(&format!("rows that are the same in both tables: {}", result.unchanged_row_count));
         }
         Err(e) => { panic!("An error occured: {:?}", e); }
     }
+    result.schema_diff = schema_diff;
     result
 }
 
+
+/// names to stage the two tables under. Both sides share one sqlite database,
+/// where names are case insensitive, so when the tables would be staged under
+/// the same name, like the same table in two sqlite files, each name gets the
+/// side it is on added to it. This also keeps the attach aliases and the
+/// comparison tables named after them apart
+fn staging_table_names(args: &argument_parser::Arguments) -> (String, String) {
+    if args.table_name_1.eq_ignore_ascii_case(&args.table_name_2) {
+        (format!("{}_1", args.table_name_1), format!("{}_2", args.table_name_2))
+    } else {
+        (args.table_name_1.clone(), args.table_name_2.clone())
+    }
+}
+
+/// when both in memory sqlite and comparison files are requested the files
+/// can't be saved, so ask before continuing without them. Returns whether the
+/// comparison tables should be created
+fn confirm_sqlite_comparison_files(args: &argument_parser::Arguments, log: &Log) -> bool {
+    if !(args.in_memory_sqlite && args.create_sqlite_comparison_files) {
+        return args.create_sqlite_comparison_files;
+    }
+
+    log.info("using in memory sqlite for data comparison,
+         this will be faster but will not save the comparison
+         data to disk, do you want to continue? (yes/no)",
+    );
+
+    // read from std in
+    let mut input = String::new();
+    if !args.auto_yes {
+        std::io::stdin().read_line(&mut input).unwrap();
+    }
+    if args.auto_yes || matches!(input.trim(), "yes" | "y") {
+        log.info("continuing with in memory sqlite");
+        false
+    } else {
+        // nothing was compared, so this exits like any other error
+        panic!("comparison cancelled");
+    }
+}
+
+/// The data source one side of the comparison is read from. Each side is
+/// opened once so anything read while discovering the schema, like the records
+/// of a csv file, is reused when the rows are loaded
+enum Source {
+    MySql(MySqlSource),
+    Postgres(PostgresSource),
+    Csv(CsvSource),
+    Sqlite(SqliteSource),
+}
+
+impl Source {
+    /// pick the data source for one side of the comparison
+    fn new(source_type: &SourceType, connection_url: &str) -> Source {
+        match source_type {
+            SourceType::MySql => Source::MySql(MySqlSource::new(connection_url)),
+            SourceType::MySqlTable(table_reference) => {
+                Source::MySql(MySqlSource::for_table(connection_url, table_reference))
+            }
+            SourceType::Postgres(source_table_name) => {
+                Source::Postgres(PostgresSource::new(connection_url, source_table_name))
+            }
+            SourceType::Csv(file_path) => Source::Csv(CsvSource::new(file_path)),
+            SourceType::Sqlite(file_path, source_table_name) => {
+                Source::Sqlite(SqliteSource::new(file_path, source_table_name))
+            }
+        }
+    }
+
+    /// discover the columns and key of the table. When a query is passed in
+    /// only the columns it returns are compared, keeping what the table
+    /// reports about the ones it has
+    fn get_table_data(&self, table_name: &str, query: &str, key_columns: &[String], log: &Log) -> TableData {
+        let mut table_data = match self {
+            Source::MySql(source) => block_on(source.get_table_data(table_name, key_columns, log)),
+            Source::Postgres(source) => block_on(source.get_table_data(table_name, key_columns, log)),
+            Source::Csv(source) => block_on(source.get_table_data(table_name, key_columns, log)),
+            Source::Sqlite(source) => block_on(source.get_table_data(table_name, key_columns, log)),
+        };
+        if query.is_empty() {
+            return table_data;
+        }
+
+        let query_columns = match self {
+            Source::MySql(source) => block_on(source.get_query_columns(query, log)),
+            Source::Postgres(source) => block_on(source.get_query_columns(query, log)),
+            Source::Csv(source) => block_on(source.get_query_columns(query, log)),
+            Source::Sqlite(source) => block_on(source.get_query_columns(query, log)),
+        };
+        if let Some(query_columns) = query_columns {
+            table_data.columns = query_columns
+                .into_iter()
+                .map(|query_column| {
+                    table_data
+                        .columns
+                        .iter()
+                        .find(|column| column.name == query_column.name)
+                        .cloned()
+                        .unwrap_or(query_column)
+                })
+                .collect();
+        }
+        table_data
+    }
+
+    /// copy the rows returned by the query into the sqlite staging table
+    fn load_rows(&self, query: &str, table_data: &TableData, log: &Log) {
+        match self {
+            Source::MySql(source) => block_on(source.load_rows(query, table_data, log)),
+            Source::Postgres(source) => block_on(source.load_rows(query, table_data, log)),
+            Source::Csv(source) => block_on(source.load_rows(query, table_data, log)),
+            Source::Sqlite(source) => block_on(source.load_rows(query, table_data, log)),
+        }
+    }
+}
+
+/// make sure every key column exists in the table, panicking with the list of
+/// missing columns if it doesn't
+fn validate_key_columns(table_data: &TableData, log: &Log) {
+    let missing_columns: Vec<&String> = table_data
+        .primary_key
+        .iter()
+        .filter(|key| !table_data.columns.iter().any(|column| &column.name == *key))
+        .collect();
+
+    if !missing_columns.is_empty() {
+        panic!(
+            "key column(s) {:?} not found in table {}",
+            missing_columns, table_data.table_name
+        );
+    }
+    log.info(&format!("joining {} on {}", table_data.table_name, table_data.primary_key.join(",")));
+}
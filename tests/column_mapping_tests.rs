@@ -0,0 +1,42 @@
+use data_comparison_tool::models::{
+    column_mapping::ColumnMapping,
+    table_data::{
+        ColumnData,
+        TableData,
+    },
+};
+
+fn table(table_name: &str, columns: &[&str]) -> TableData {
+    TableData::new(
+        table_name.to_string(),
+        columns.iter().map(|column| ColumnData::new(column.to_string(), "TEXT".to_string())).collect(),
+        vec!["id".to_string()],
+    )
+}
+
+#[test]
+fn pairs_same_and_custom_mapped_columns(){
+    let table_1 = table("table_1", &["id", "customer_name", "email", "legacy_flag"]);
+    let table_2 = table("table_2", &["id", "cust_nm", "email", "created_at"]);
+    let mapping = vec![("customer_name".to_string(), "cust_nm".to_string())];
+
+    let column_mapping = ColumnMapping::new(&table_1, &table_2, &mapping);
+    let pairs: Vec<(&str, &str)> = column_mapping
+        .pairs
+        .iter()
+        .map(|pair| (pair.column_1.as_str(), pair.column_2.as_str()))
+        .collect();
+
+    assert_eq!(pairs, vec![("customer_name", "cust_nm"), ("email", "email")]);
+    assert_eq!(column_mapping.unmapped_table_1_columns, vec!["legacy_flag"]);
+    assert_eq!(column_mapping.unmapped_table_2_columns, vec!["created_at"]);
+}
+
+#[test]
+#[should_panic(expected = "mapped column missing not found")]
+fn unknown_mapped_column_panics(){
+    let table_1 = table("table_1", &["id", "name"]);
+    let table_2 = table("table_2", &["id", "name"]);
+    let mapping = vec![("missing".to_string(), "name".to_string())];
+    ColumnMapping::new(&table_1, &table_2, &mapping);
+}
@@ -0,0 +1,122 @@
+#![allow(dead_code)]
+
+use data_comparison_tool::{
+    interface::{
+        log::Log,
+        log_options::{
+            LogOutput,
+            LogVerbosity,
+        },
+    },
+    models::{
+        changed_row::{
+            ChangedRow,
+            ColumnDifference,
+        },
+        column_mapping::ColumnPair,
+        comparison_data::ComparisonData,
+        result_row::ResultRow,
+        table_data::TableData,
+        value::Value,
+    },
+};
+use std::{
+    path::PathBuf,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+/// log for tests that doesn't depend on the arguments or env vars the tests
+/// are run with
+pub fn test_log() -> Log {
+    Log::with_settings(LogOutput::Console, LogVerbosity::Info)
+}
+
+/// A directory under the system temp directory that only the test creating it
+/// writes to, removed along with everything in it when dropped
+pub struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    pub fn new(test_name: &str) -> TestDir {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let path = std::env::temp_dir().join(format!(
+            "data_comparison_tool_{}_{}_{}",
+            test_name,
+            std::process::id(),
+            nanos
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        TestDir { path }
+    }
+
+    /// path of a file inside of the directory
+    pub fn file(&self, file_name: &str) -> String {
+        self.path.join(file_name).to_str().unwrap().to_string()
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// name with a quote, a comma and double quotes in it, which every exporter
+/// has to escape in its own way
+pub const QUOTED_NAME: &str = "O'Brien, \"Jr\"";
+
+/// time the rows of `people_comparison` were created at
+pub fn created_at() -> chrono::NaiveDateTime {
+    chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(12, 30, 0).unwrap()
+}
+
+/// comparison of a people table with columns id, name, balance and created,
+/// staged under different names on each side. There is one row only in each
+/// table and one changed row, between them holding nulls, a name with quotes,
+/// markup and decimals so the exporters can be checked on each of them
+pub fn people_comparison() -> ComparisonData {
+    let columns = vec!["id".to_string(), "name".to_string(), "balance".to_string(), "created".to_string()];
+    let only_left_row = ResultRow::new(
+        columns.clone(),
+        vec![Value::Int(3), Value::Text(QUOTED_NAME.to_string()), Value::Decimal("10.50".to_string()), Value::Null],
+    );
+    let only_right_row = ResultRow::new(
+        columns,
+        vec![Value::Int(4), Value::Null, Value::Decimal("0.10".to_string()), Value::DateTime(created_at())],
+    );
+    let mut changed_row = ChangedRow::new(
+        vec![("id".to_string(), Value::Int(2))],
+        vec![
+            ColumnDifference {
+                column: "name".to_string(),
+                left_value: Value::Text("<b>bob</b>".to_string()),
+                right_value: Value::Text("bobby".to_string()),
+            },
+            ColumnDifference {
+                column: "balance".to_string(),
+                left_value: Value::Decimal("1.10".to_string()),
+                right_value: Value::Null,
+            },
+        ],
+    );
+    changed_row.unchanged_values = vec![("created".to_string(), Value::DateTime(created_at()))];
+
+    let mut comparison_data = ComparisonData::new(vec![only_left_row], vec![only_right_row], vec![changed_row]);
+    comparison_data.table_data_1 = TableData::new("people20261018120000_1".to_string(), Vec::new(), vec!["id".to_string()]);
+    comparison_data.table_data_1.source_table = "people".to_string();
+    comparison_data.table_data_2 = TableData::new("people20261018120000_2".to_string(), Vec::new(), vec!["id".to_string()]);
+    comparison_data.table_data_2.source_schema = "shop".to_string();
+    comparison_data.table_data_2.source_table = "people".to_string();
+    for column in ["name", "balance", "created"] {
+        comparison_data.column_mapping.pairs.push(ColumnPair {
+            column_1: column.to_string(),
+            column_2: column.to_string(),
+        });
+    }
+    comparison_data.unchanged_row_count = 5;
+    comparison_data
+}
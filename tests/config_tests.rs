@@ -0,0 +1,173 @@
+use data_comparison_tool::interface::{
+    argument_parser::{
+        Arguments,
+        OutputFileType,
+        SourceType,
+    },
+    config::Config,
+};
+
+mod common;
+
+#[test]
+fn command_line_overrides_config_file(){
+    let test_dir = common::TestDir::new("command_line_overrides_config_file");
+    let config_path = test_dir.file("run.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+table_1 = "postgres:public.orders"
+table_2 = "right.csv"
+query_1 = "select * from orders where paid = 1"
+key = ["id"]
+output = "diff.json"
+auto_yes = true
+
+[map]
+name = "full_name"
+"#,
+    )
+    .unwrap();
+
+    let args = Arguments::from_args(vec![
+        format!("-config={}", config_path),
+        "-t2=other.csv".to_string(),
+    ]);
+    assert!(matches!(&args.source_type_1, SourceType::Postgres(table_reference) if table_reference == "public.orders"));
+    assert!(matches!(&args.source_type_2, SourceType::Csv(file_path) if file_path == "other.csv"));
+    assert_eq!(args.mysql_query_1, "select * from orders where paid = 1");
+    assert_eq!(args.key_columns, vec!["id"]);
+    assert_eq!(args.column_mapping, vec![("name".to_string(), "full_name".to_string())]);
+    assert!(matches!(args.output_file_type, OutputFileType::Json));
+    assert!(args.auto_yes);
+}
+
+#[test]
+fn switches_turned_off_in_config_file(){
+    let test_dir = common::TestDir::new("switches_turned_off_in_config_file");
+    let config_path = test_dir.file("run.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+clean = false
+create_in_flight = false
+auto_yes = true
+"#,
+    )
+    .unwrap();
+
+    let args = Arguments::from_args(vec![
+        format!("-config={}", config_path),
+        "-no-auto-yes".to_string(),
+    ]);
+    assert!(!args.clean);
+    assert!(!args.create_sqlite_comparison_files);
+    assert!(!args.auto_yes);
+}
+
+#[test]
+fn save_config_round_trips(){
+    let test_dir = common::TestDir::new("save_config_round_trips");
+    let config_path = test_dir.file("run.toml");
+    Arguments::from_args(vec![
+        "-t1=postgres:public.orders".to_string(),
+        "-t2=orders.csv".to_string(),
+        "-map=name:full_name".to_string(),
+        "-in-memory".to_string(),
+        format!("-save-config={}", config_path),
+    ]);
+
+    let config = Config::load(&config_path);
+    assert_eq!(config.table_1.as_deref(), Some("postgres:public.orders"));
+    assert_eq!(config.table_2.as_deref(), Some("orders.csv"));
+    assert_eq!(config.map.as_ref().unwrap()["name"], "full_name");
+    assert_eq!(config.in_memory, Some(true));
+    assert_eq!(Config::from_flags(&config.to_flags()), config);
+}
+
+#[test]
+fn config_jobs_inherit_shared_settings(){
+    let test_dir = common::TestDir::new("config_jobs_inherit_shared_settings");
+    let config_path = test_dir.file("run.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+key = ["id"]
+output = "out/diff.csv"
+auto_yes = true
+
+[[job]]
+name = "orders"
+table_1 = "orders_1.csv"
+table_2 = "orders_2.csv"
+
+[[job]]
+table_1 = "customers_1.csv"
+table_2 = "customers_2.csv"
+key = ["customer_id"]
+output = "customers.json"
+"#,
+    )
+    .unwrap();
+
+    let args = Arguments::from_args(vec![
+        format!("-config={}", config_path),
+        "-verbose".to_string(),
+    ]);
+    assert_eq!(args.jobs.len(), 2);
+
+    let orders = &args.jobs[0];
+    assert_eq!(orders.job_name, "orders");
+    assert!(matches!(&orders.source_type_1, SourceType::Csv(file_path) if file_path == "orders_1.csv"));
+    assert_eq!(orders.key_columns, vec!["id"]);
+    assert_eq!(orders.output_file_name, "out/orders_diff.csv");
+    assert_eq!(orders.sqlite_file_name, "db_orders.sqlite3");
+    assert!(orders.auto_yes);
+    assert!(orders.verbose);
+
+    let customers = &args.jobs[1];
+    assert_eq!(customers.job_name, "job_2");
+    assert_eq!(customers.sqlite_file_name, "db_job_2.sqlite3");
+    assert_eq!(customers.key_columns, vec!["customer_id"]);
+    assert_eq!(customers.output_file_name, "customers.json");
+    assert!(matches!(customers.output_file_type, OutputFileType::Json));
+}
+
+#[test]
+fn read_settings_from_env_vars(){
+    let config = Config::from_env_vars(vec![
+        ("DCT_LEFT_TABLE".to_string(), "mysql:shop.orders".to_string()),
+        ("DCT_LEFT_URL".to_string(), "mysql://reader@db-1:3306/shop".to_string()),
+        ("DCT_RIGHT_URL".to_string(), "mysql://reader@db-2:3306/shop".to_string()),
+        ("DCT_OUTPUT".to_string(), "diff.parquet".to_string()),
+        ("DCT_LOG_LEVEL".to_string(), "warning".to_string()),
+        ("DCT_AUTO_YES".to_string(), "true".to_string()),
+        ("DCT_SYNC".to_string(), "0".to_string()),
+        ("DCT_CLEAN".to_string(), "false".to_string()),
+        ("PATH".to_string(), "/usr/bin".to_string()),
+        ("DCT_LEFT_TABEL".to_string(), "orders.csv".to_string()),
+    ]);
+    assert_eq!(config.table_1.as_deref(), Some("mysql:shop.orders"));
+    assert_eq!(config.url_1.as_deref(), Some("mysql://reader@db-1:3306/shop"));
+    assert_eq!(config.url_2.as_deref(), Some("mysql://reader@db-2:3306/shop"));
+    assert_eq!(config.output.as_deref(), Some("diff.parquet"));
+    assert_eq!(config.log_level.as_deref(), Some("warning"));
+    assert_eq!(config.auto_yes, Some(true));
+    assert_eq!(config.sync, Some(false));
+    assert_eq!(config.clean, Some(false));
+}
+
+#[test]
+#[should_panic(expected = "column mapping name should be in the form column_1:column_2")]
+fn reject_malformed_map_env_var(){
+    Config::from_env_vars(vec![("DCT_MAP".to_string(), "name".to_string())]);
+}
+
+#[test]
+#[should_panic(expected = "queries can't be run against csv files")]
+fn reject_query_for_csv_table(){
+    Arguments::from_args(vec![
+        "-t1=orders.csv".to_string(),
+        "-q1=select * from orders".to_string(),
+    ]);
+}
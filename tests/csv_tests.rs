@@ -1,4 +1,87 @@
+use data_comparison_tool::{
+    datastore::csv::{
+        export_comparison_data_to_csv,
+        export_comparison_data_to_unified_csv,
+        CsvOptions,
+        infer_column_type,
+        sanitize_name,
+    },
+    models::{
+        comparison_data::ComparisonData,
+        result_row::ResultRow,
+        value::Value,
+    },
+};
+
+mod common;
+
 #[ test]
 fn create_new_csv_file(){
 
 }
+
+#[test]
+fn infer_csv_column_types(){
+    assert_eq!(infer_column_type(&["1", "2", "", "-30"]), "INTEGER");
+    assert_eq!(infer_column_type(&["1", "2.5", "3"]), "REAL");
+    assert_eq!(infer_column_type(&["1", "abc"]), "TEXT");
+    assert_eq!(infer_column_type(&["", ""]), "TEXT");
+}
+
+#[test]
+fn sanitize_csv_names(){
+    assert_eq!(sanitize_name("customer name"), "customer_name");
+    assert_eq!(sanitize_name("2024-extract"), "t_2024_extract");
+}
+
+#[test]
+fn export_unified_csv(){
+    let log = common::test_log();
+    let comparison_data = common::people_comparison();
+
+    let test_dir = common::TestDir::new("export_unified_csv");
+    let output_file_name = test_dir.file("diff.csv");
+    export_comparison_data_to_unified_csv(&comparison_data, &output_file_name, &CsvOptions::default(), &log).unwrap();
+
+    // the name with a comma and quotes in it is quoted, nulls are empty and
+    // decimals keep their trailing zeros
+    let lines: Vec<String> = std::fs::read_to_string(&output_file_name)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "_change,id,name__left,name__right,balance__left,balance__right,created__left,created__right",
+            "only_left,3,\"O'Brien, \"\"Jr\"\"\",<missing>,10.50,<missing>,,<missing>",
+            "only_right,4,<missing>,,<missing>,0.10,<missing>,2024-05-01 12:30:00",
+            "changed,2,<b>bob</b>,bobby,1.10,,2024-05-01 12:30:00,2024-05-01 12:30:00",
+        ]
+    );
+}
+
+#[test]
+fn export_csv_with_headers_and_options(){
+    let log = common::test_log();
+    let unique_row = ResultRow::new(
+        vec!["id".to_string(), "name".to_string()],
+        vec![Value::Int(3), Value::Null],
+    );
+    let comparison_data = ComparisonData::new(vec![unique_row], Vec::new(), Vec::new());
+    let options = CsvOptions {
+        delimiter: b';',
+        quote_style: csv::QuoteStyle::Always,
+        null_value: "NULL".to_string(),
+        terminator: csv::Terminator::Any(b'\n'),
+    };
+
+    let test_dir = common::TestDir::new("export_csv_with_headers_and_options");
+    export_comparison_data_to_csv(&comparison_data, &test_dir.file("diff.csv"), &options, &log).unwrap();
+
+    let unique_file_name = test_dir.file("unique_table_1_rows_diff.csv");
+    assert_eq!(
+        std::fs::read_to_string(unique_file_name).unwrap(),
+        "\"id\";\"name\"\n\"3\";\"NULL\"\n"
+    );
+}
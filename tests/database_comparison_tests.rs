@@ -0,0 +1,37 @@
+use data_comparison_tool::models::database_comparison::{
+    DatabaseComparison,
+    TablePairing,
+    TableStatus,
+    TableSummary,
+};
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn pair_tables_by_name_with_patterns(){
+    let tables_1 = names(&["customers", "orders", "orders_archive", "tmp_import"]);
+    let tables_2 = names(&["orders", "orders_archive", "products", "tmp_export"]);
+
+    let pairing = TablePairing::new(&tables_1, &tables_2, &[], &names(&["tmp_*"]));
+    assert_eq!(pairing.paired_tables, names(&["orders", "orders_archive"]));
+    assert_eq!(pairing.only_in_database_1, names(&["customers"]));
+    assert_eq!(pairing.only_in_database_2, names(&["products"]));
+
+    let pairing = TablePairing::new(&tables_1, &tables_2, &names(&["orders*", "products"]), &names(&["*_archive"]));
+    assert_eq!(pairing.paired_tables, names(&["orders"]));
+    assert!(pairing.only_in_database_1.is_empty());
+    assert_eq!(pairing.only_in_database_2, names(&["products"]));
+
+    let report = DatabaseComparison {
+        database_1: "shop".to_string(),
+        database_2: "shop_copy".to_string(),
+        only_in_database_1: Vec::new(),
+        only_in_database_2: Vec::new(),
+        tables: vec![TableSummary::new("orders", None)],
+    };
+    assert_eq!(report.tables[0].status, TableStatus::Failed);
+    assert_eq!(report.failed_tables(), vec!["orders"]);
+    assert!(!report.has_differences());
+}
@@ -0,0 +1,17 @@
+use data_comparison_tool::datastore::html::comparison_data_to_html;
+
+mod common;
+
+#[test]
+fn render_changed_cells_escaped_and_offline(){
+    let comparison_data = common::people_comparison();
+
+    let html = comparison_data_to_html(&comparison_data);
+    assert!(html.contains("<span class=\"old\">&lt;b&gt;bob&lt;/b&gt;</span>"));
+    assert!(html.contains("<span class=\"new\"><span class=\"null\">NULL</span></span>"));
+    assert!(html.contains("O&#39;Brien, &quot;Jr&quot;"));
+    assert!(!html.contains(common::QUOTED_NAME));
+    assert!(html.contains("10.50"));
+    assert!(!html.contains("http"));
+    assert!(html.contains("<h1>people vs shop.people</h1>"));
+}
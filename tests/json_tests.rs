@@ -0,0 +1,31 @@
+use data_comparison_tool::datastore::json::export_comparison_data_to_json;
+
+mod common;
+
+#[test]
+fn export_comparison_data_as_json(){
+    let log = common::test_log();
+    let comparison_data = common::people_comparison();
+
+    let test_dir = common::TestDir::new("export_comparison_data_as_json");
+    let output_file_name = test_dir.file("diff.json");
+    export_comparison_data_to_json(&comparison_data, &output_file_name, &log);
+
+    let document: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&output_file_name).unwrap()).unwrap();
+    assert_eq!(document["metadata"]["table_1"], "people");
+    assert_eq!(document["metadata"]["table_2"], "shop.people");
+    assert_eq!(document["metadata"]["changed_count"], 1);
+    assert_eq!(document["metadata"]["unchanged_count"], 5);
+    // quotes survive as part of the string and decimals stay strings so no
+    // digits are lost
+    assert_eq!(document["only_left"][0]["id"], 3);
+    assert_eq!(document["only_left"][0]["name"], common::QUOTED_NAME);
+    assert_eq!(document["only_left"][0]["balance"], "10.50");
+    assert!(document["only_left"][0]["created"].is_null());
+    assert_eq!(document["only_right"][0]["created"], "2024-05-01 12:30:00");
+    assert_eq!(document["changed"][0]["key"]["id"], 2);
+    assert_eq!(document["changed"][0]["differences"][0]["right_value"], "bobby");
+    assert_eq!(document["changed"][0]["differences"][1]["left_value"], "1.10");
+    assert!(document["changed"][0]["differences"][1]["right_value"].is_null());
+}
@@ -0,0 +1,61 @@
+use data_comparison_tool::{
+    datastore::junit::{
+        comparison_results_to_junit,
+        JunitTestCase,
+    },
+    models::{
+        comparison_data::ComparisonData,
+        result_row::ResultRow,
+        table_data::TableData,
+        value::Value,
+    },
+};
+
+#[test]
+fn fail_test_cases_with_differences(){
+    let mut identical = ComparisonData::new(Vec::new(), Vec::new(), Vec::new());
+    // staged tables are named after the date they were loaded on, the test
+    // cases after the tables they were loaded from
+    identical.table_data_1 = TableData::new("orders20261018120000".to_string(), Vec::new(), Vec::new());
+    identical.table_data_1.source_table = "orders".to_string();
+    identical.table_data_2 = TableData::new("orders_copy20261018120000".to_string(), Vec::new(), Vec::new());
+    identical.table_data_2.source_schema = "shop".to_string();
+    identical.table_data_2.source_table = "orders_copy".to_string();
+
+    let unique_row = ResultRow::new(vec!["id".to_string()], vec![Value::Int(3)]);
+    let mut different = ComparisonData::new(vec![unique_row], Vec::new(), Vec::new());
+    different.table_data_1 = TableData::new("items<1>".to_string(), Vec::new(), Vec::new());
+    different.table_data_2 = TableData::new("items_copy".to_string(), Vec::new(), Vec::new());
+
+    assert!(!identical.has_differences());
+    assert!(different.has_differences());
+
+    let xml = comparison_results_to_junit(&[JunitTestCase::from_result(&identical), JunitTestCase::from_result(&different)]);
+    assert!(xml.contains("tests=\"2\" failures=\"1\" errors=\"0\""));
+    assert!(xml.contains("<testcase classname=\"data_comparison_tool\" name=\"orders vs shop.orders_copy\" />"));
+    assert!(xml.contains("name=\"items&lt;1&gt; vs items_copy\">"));
+    assert!(xml.contains("<failure type=\"differences\" message=\"1 rows only in items&lt;1&gt;"));
+}
+
+#[test]
+fn report_failed_comparisons_as_errors(){
+    let mut identical = ComparisonData::new(Vec::new(), Vec::new(), Vec::new());
+    identical.table_data_1 = TableData::new("orders".to_string(), Vec::new(), Vec::new());
+    identical.table_data_2 = TableData::new("orders_copy".to_string(), Vec::new(), Vec::new());
+
+    let xml = comparison_results_to_junit(&[
+        JunitTestCase {
+            name: "orders".to_string(),
+            outcome: Ok(&identical),
+        },
+        JunitTestCase {
+            name: "customers".to_string(),
+            outcome: Err("table customers has no primary key or unique index to join on"),
+        },
+    ]);
+    assert!(xml.contains("tests=\"2\" failures=\"0\" errors=\"1\""));
+    assert!(xml.contains("<testcase classname=\"data_comparison_tool\" name=\"orders\" />"));
+    assert!(xml.contains(
+        "<testcase classname=\"data_comparison_tool\" name=\"customers\">\n      <error type=\"error\" message=\"table customers has no primary key or unique index to join on\">"
+    ));
+}
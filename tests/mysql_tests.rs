@@ -0,0 +1,92 @@
+use data_comparison_tool::{
+    datastore::transformer::mysql_type_to_sqlite_type,
+    interface::argument_parser::Arguments,
+    models::value::Value,
+    processor,
+};
+use chrono::NaiveDate;
+
+mod common;
+
+#[test]
+fn map_mysql_types_to_sqlite(){
+    assert_eq!(mysql_type_to_sqlite_type("INT"), "INTEGER");
+    assert_eq!(mysql_type_to_sqlite_type("BIGINT UNSIGNED"), "INTEGER");
+    assert_eq!(mysql_type_to_sqlite_type("BOOLEAN"), "INTEGER");
+    assert_eq!(mysql_type_to_sqlite_type("DOUBLE"), "REAL");
+    assert_eq!(mysql_type_to_sqlite_type("DECIMAL"), "TEXT");
+    assert_eq!(mysql_type_to_sqlite_type("DATETIME"), "TEXT");
+    assert_eq!(mysql_type_to_sqlite_type("VARCHAR"), "TEXT");
+    assert_eq!(mysql_type_to_sqlite_type("VARBINARY"), "BLOB");
+}
+
+/// stages two mysql tables and compares them, only run when
+/// MYSQL_CONNECTION_STRING_USER points at a database the tests can create
+/// tables in, like the mysql service of the docker compose file
+#[test]
+fn stage_mysql_values_by_type(){
+    let Ok(connection_url) = std::env::var("MYSQL_CONNECTION_STRING_USER") else {
+        println!("MYSQL_CONNECTION_STRING_USER is not set, skipping");
+        return;
+    };
+    async_std::task::block_on(async {
+        let pool = sqlx::mysql::MySqlPoolOptions::new().connect(&connection_url).await.unwrap();
+        for (table_name, rows) in [
+            ("dct_test_orders_1", "(1, 'o''brien', 10.50, '2024-01-02 03:04:05'), (2, null, 0.10, null)"),
+            ("dct_test_orders_2", "(1, 'o''brien', 10.50, '2024-01-02 03:04:05'), (2, 'bob', 0.10, null)"),
+        ] {
+            sqlx::query(&format!("drop table if exists {}", table_name)).execute(&pool).await.unwrap();
+            sqlx::query(&format!(
+                "create table {} (id int primary key, name varchar(20), amount decimal(10, 2), ordered_at datetime)",
+                table_name
+            ))
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(&format!("insert into {} values {}", table_name, rows)).execute(&pool).await.unwrap();
+        }
+        pool.close().await;
+    });
+
+    let test_dir = common::TestDir::new("stage_mysql_values_by_type");
+    let mut arguments = Arguments::from_args(vec![
+        "-t1=mysql:dct_test_orders_1".to_string(),
+        "-t2=mysql:dct_test_orders_2".to_string(),
+        "-no-c".to_string(),
+        "-auto-yes".to_string(),
+    ]);
+    arguments.sqlite_file_name = test_dir.file("staging.sqlite3");
+    let result = processor::run_comparison(&arguments, &common::test_log());
+
+    // the quote, decimal and datetime survive staging, so the first row matches
+    assert_eq!(result.unchanged_row_count, 1);
+    assert_eq!(result.changed_rows.len(), 1);
+    let changed_row = &result.changed_rows[0];
+    assert_eq!(changed_row.differences.len(), 1);
+    assert_eq!(changed_row.differences[0].left_value, Value::Null);
+    assert_eq!(changed_row.differences[0].right_value, Value::Text("bob".to_string()));
+    assert_eq!(changed_row.unchanged_value("amount"), Some(&Value::Decimal("0.10".to_string())));
+    assert_eq!(changed_row.unchanged_value("ordered_at"), Some(&Value::Null));
+
+    // read the staged values of the first row back out of the first table
+    let staged_row = async_std::task::block_on(async {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .connect(&format!("sqlite://{}", arguments.sqlite_file_name))
+            .await
+            .unwrap();
+        let row: (String, String, String) = sqlx::query_as(&format!(
+            "select name, amount, ordered_at from {} where id = 1",
+            result.table_data_1.table_name
+        ))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        row
+    });
+    assert_eq!(staged_row.0, "o'brien");
+    assert_eq!(staged_row.1, "10.50");
+    assert_eq!(
+        Value::from_text(staged_row.2, "DATETIME"),
+        Value::DateTime(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap().and_hms_opt(3, 4, 5).unwrap())
+    );
+}
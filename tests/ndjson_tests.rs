@@ -0,0 +1,49 @@
+use data_comparison_tool::{
+    datastore::ndjson::NdjsonWriter,
+    models::diff_record::{
+        DiffRecord,
+        StreamedCounts,
+    },
+};
+
+mod common;
+
+#[test]
+fn write_one_tagged_record_per_line(){
+    let log = common::test_log();
+    let comparison_data = common::people_comparison();
+    let test_dir = common::TestDir::new("write_one_tagged_record_per_line");
+    let output_file_name = test_dir.file("diff.ndjson");
+
+    let mut writer = NdjsonWriter::new(&output_file_name);
+    for row in comparison_data.unique_table_1_rows.iter() {
+        writer.write_record(&DiffRecord::OnlyLeft { row: row.clone() });
+    }
+    for row in comparison_data.unique_table_2_rows.iter() {
+        writer.write_record(&DiffRecord::OnlyRight { row: row.clone() });
+    }
+    for row in comparison_data.changed_rows.iter() {
+        writer.write_record(&DiffRecord::Changed(row.clone()));
+    }
+    let counts = writer.finish(&log);
+    assert_eq!(counts, StreamedCounts { only_left: 1, only_right: 1, changed: 1 });
+
+    // a quote inside of a value can't end the line early, so every line
+    // parses on its own
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&output_file_name)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["type"], "only_left");
+    assert_eq!(lines[0]["row"]["name"], common::QUOTED_NAME);
+    assert_eq!(lines[0]["row"]["balance"], "10.50");
+    assert!(lines[0]["row"]["created"].is_null());
+    assert_eq!(lines[1]["type"], "only_right");
+    assert!(lines[1]["row"]["name"].is_null());
+    assert_eq!(lines[2]["type"], "changed");
+    assert_eq!(lines[2]["key"]["id"], 2);
+    assert_eq!(lines[2]["differences"][1]["left_value"], "1.10");
+    assert!(lines[2]["differences"][1]["right_value"].is_null());
+}
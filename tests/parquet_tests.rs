@@ -0,0 +1,57 @@
+use arrow_array::{
+    Array,
+    Int64Array,
+    StringArray,
+    TimestampMicrosecondArray,
+};
+use arrow_schema::{
+    DataType,
+    TimeUnit,
+};
+use data_comparison_tool::datastore::parquet::export_comparison_data_to_parquet;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+mod common;
+
+#[test]
+fn export_typed_parquet(){
+    let log = common::test_log();
+    let comparison_data = common::people_comparison();
+
+    let test_dir = common::TestDir::new("export_typed_parquet");
+    let output_file_name = test_dir.file("diff.parquet");
+    export_comparison_data_to_parquet(&comparison_data, &output_file_name, &log).unwrap();
+
+    let file = std::fs::File::open(&output_file_name).unwrap();
+    let batch = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let schema = batch.schema();
+    assert_eq!(schema.field_with_name("id").unwrap().data_type(), &DataType::Int64);
+    // decimals are written as text so no digits are lost
+    assert_eq!(schema.field_with_name("balance__left").unwrap().data_type(), &DataType::Utf8);
+    assert_eq!(
+        schema.field_with_name("created__right").unwrap().data_type(),
+        &DataType::Timestamp(TimeUnit::Microsecond, None)
+    );
+
+    let change_types = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(change_types.value(0), "only_left");
+    assert_eq!(change_types.value(1), "only_right");
+    assert_eq!(change_types.value(2), "changed");
+    let ids = batch.column(1).as_any().downcast_ref::<Int64Array>().unwrap();
+    assert_eq!(ids.value(2), 2);
+    let names_left = batch.column(2).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(names_left.value(0), common::QUOTED_NAME);
+    let balances_left = batch.column(4).as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(balances_left.value(0), "10.50");
+    assert_eq!(balances_left.value(2), "1.10");
+    assert!(batch.column(5).is_null(2));
+    let created_right = batch.column(7).as_any().downcast_ref::<TimestampMicrosecondArray>().unwrap();
+    assert_eq!(created_right.value(1), common::created_at().and_utc().timestamp_micros());
+    assert!(batch.column(6).is_null(0));
+}
@@ -0,0 +1,68 @@
+use data_comparison_tool::{
+    datastore::transformer::postgres_type_to_sqlite_type,
+    interface::argument_parser::Arguments,
+    processor,
+};
+
+mod common;
+
+#[test]
+fn map_postgres_types_to_sqlite(){
+    assert_eq!(postgres_type_to_sqlite_type("integer"), "INTEGER");
+    assert_eq!(postgres_type_to_sqlite_type("boolean"), "INTEGER");
+    assert_eq!(postgres_type_to_sqlite_type("double precision"), "REAL");
+    assert_eq!(postgres_type_to_sqlite_type("numeric"), "TEXT");
+    assert_eq!(postgres_type_to_sqlite_type("bytea"), "BLOB");
+    assert_eq!(postgres_type_to_sqlite_type("int4"), "INTEGER");
+    assert_eq!(postgres_type_to_sqlite_type("float8"), "REAL");
+}
+
+/// compares two postgres tables through queries that select some of their
+/// columns, only run when POSTGRES_CONNECTION_STRING points at a database the
+/// tests can create tables in, like the postgres service of the docker
+/// compose file
+#[test]
+fn compare_postgres_queries_on_their_own_columns(){
+    let Ok(connection_url) = std::env::var("POSTGRES_CONNECTION_STRING") else {
+        println!("POSTGRES_CONNECTION_STRING is not set, skipping");
+        return;
+    };
+    async_std::task::block_on(async {
+        let pool = sqlx::postgres::PgPoolOptions::new().connect(&connection_url).await.unwrap();
+        for (table_name, rows) in [
+            ("dct_test_orders_1", "(1, 'alice', 10.50, true), (2, 'bob', 0.10, true), (3, 'carol', 7, false)"),
+            ("dct_test_orders_2", "(1, 'alicia', 10.50, true), (2, 'bob', 0.20, false), (4, 'dave', 1, true)"),
+        ] {
+            sqlx::query(&format!("drop table if exists public.{}", table_name)).execute(&pool).await.unwrap();
+            sqlx::query(&format!(
+                "create table public.{} (id integer primary key, name text, amount numeric(10, 2), paid boolean)",
+                table_name
+            ))
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(&format!("insert into public.{} values {}", table_name, rows)).execute(&pool).await.unwrap();
+        }
+        pool.close().await;
+    });
+
+    // the queries leave the names out, so alice and alicia are the same row
+    let test_dir = common::TestDir::new("compare_postgres_queries_on_their_own_columns");
+    let mut arguments = Arguments::from_args(vec![
+        "-t1=postgres:public.dct_test_orders_1".to_string(),
+        "-t2=postgres:public.dct_test_orders_2".to_string(),
+        "-q1=select id, amount from public.dct_test_orders_1 where paid".to_string(),
+        "-q2=select amount, id, paid as settled from public.dct_test_orders_2".to_string(),
+        "-no-c".to_string(),
+        "-auto-yes".to_string(),
+    ]);
+    arguments.sqlite_file_name = test_dir.file("staging.sqlite3");
+    let result = processor::run_comparison(&arguments, &common::test_log());
+
+    let column_names: Vec<&str> = result.table_data_2.columns.iter().map(|column| column.name.as_str()).collect();
+    assert_eq!(column_names, vec!["amount", "id", "settled"]);
+    assert_eq!(result.unique_table_1_rows.len(), 0);
+    assert_eq!(result.unique_table_2_rows.len(), 1);
+    assert_eq!(result.changed_rows.len(), 1);
+    assert_eq!(result.unchanged_row_count, 1);
+}
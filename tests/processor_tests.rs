@@ -2,8 +2,11 @@ use data_comparison_tool::interface::{
     argument_parser,
     log
 };
+use data_comparison_tool::models::database_comparison::TableSummary;
 use data_comparison_tool::processor;
 
+mod common;
+
 /// a defaultly initialized argument should always pass
 /// this test
 #[test]
@@ -12,3 +15,146 @@ pub fn run_comparison_no_terminal_default_arg() {
     let log = log::Log::new(&arguments);
     processor::run_comparison(&arguments, &log);
 }
+
+/// create a sqlite database file with a table made by the statements passed in
+fn create_sqlite_file(file_path: &str, statements: &[&str]) {
+    async_std::task::block_on(async {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .connect(&format!("sqlite://{}?mode=rwc", file_path))
+            .await
+            .unwrap();
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool.close().await;
+    });
+}
+
+/// arguments comparing a table in two sqlite files in place, staging the
+/// comparison in the test directory instead of the current one
+fn sqlite_file_arguments(test_dir: &common::TestDir, table_name: &str, extra_flags: &[&str]) -> argument_parser::Arguments {
+    let mut cli_args = vec![
+        format!("-t1={}:{}", test_dir.file("left.sqlite"), table_name),
+        format!("-t2={}:{}", test_dir.file("right.sqlite"), table_name),
+        "-no-c".to_string(),
+        "-auto-yes".to_string(),
+    ];
+    cli_args.extend(extra_flags.iter().map(|flag| flag.to_string()));
+    let mut arguments = argument_parser::Arguments::from_args(cli_args);
+    arguments.sqlite_file_name = test_dir.file("staging.sqlite3");
+    arguments
+}
+
+#[test]
+fn compare_sqlite_files_in_place() {
+    let test_dir = common::TestDir::new("compare_sqlite_files_in_place");
+    create_sqlite_file(&test_dir.file("left.sqlite"), &[
+        "create table items (id integer primary key, name text, amount real)",
+        "insert into items values (1, 'alice', 10), (2, 'bob', null), (3, 'carol', null), (4, 'dave', 40)",
+    ]);
+    create_sqlite_file(&test_dir.file("right.sqlite"), &[
+        "create table items (id integer primary key, name text, amount real)",
+        "insert into items values (2, 'bob', null), (3, 'carol', 30), (4, 'dave', 41), (5, 'eve', 50)",
+    ]);
+
+    let arguments = sqlite_file_arguments(&test_dir, "items", &[]);
+    let result = processor::run_comparison(&arguments, &common::test_log());
+
+    assert_eq!(result.unique_table_1_rows.len(), 1);
+    assert_eq!(result.unique_table_2_rows.len(), 1);
+    // a null on only one side is a change, a null on both sides isn't
+    assert_eq!(result.changed_rows.len(), 2);
+    assert_eq!(result.unchanged_row_count, 1);
+    assert_eq!(result.table_data_1.source_name(), "items");
+}
+
+#[test]
+fn count_streamed_rows_by_kind() {
+    let test_dir = common::TestDir::new("count_streamed_rows_by_kind");
+    create_sqlite_file(&test_dir.file("left.sqlite"), &[
+        "create table items (id integer primary key, name text)",
+        "insert into items values (1, 'alice'), (2, 'bob'), (3, 'carol'), (4, 'dave')",
+    ]);
+    create_sqlite_file(&test_dir.file("right.sqlite"), &[
+        "create table items (id integer primary key, name text)",
+        "insert into items values (2, 'bobby'), (4, 'dave'), (5, 'eve'), (6, 'frank'), (7, 'grace')",
+    ]);
+
+    let output_file_name = test_dir.file("diff.ndjson");
+    let arguments = sqlite_file_arguments(&test_dir, "items", &[&format!("-output={}", output_file_name)]);
+    let result = processor::run_comparison(&arguments, &common::test_log());
+
+    // the differences only went to the file, but they are still counted as
+    // the kind of difference they are
+    assert!(result.changed_rows.is_empty());
+    assert_eq!(result.unique_table_1_row_count(), 2);
+    assert_eq!(result.unique_table_2_row_count(), 3);
+    assert_eq!(result.changed_row_count(), 1);
+    assert_eq!(std::fs::read_to_string(&output_file_name).unwrap().lines().count(), 6);
+
+    let summary = TableSummary::new("items", Some(&result));
+    assert_eq!(summary.only_left_count, 2);
+    assert_eq!(summary.only_right_count, 3);
+    assert_eq!(summary.changed_count, 1);
+    assert_eq!(summary.unchanged_count, 1);
+}
+
+#[test]
+fn compare_sqlite_files_on_two_column_key() {
+    let test_dir = common::TestDir::new("compare_sqlite_files_on_two_column_key");
+    // the same code is used in both regions, so joining on either column
+    // alone would pair up the wrong rows
+    create_sqlite_file(&test_dir.file("left.sqlite"), &[
+        "create table stock (code text, region integer, quantity integer)",
+        "create unique index stock_region_code on stock (region, code)",
+        "insert into stock values ('a', 1, 5), ('a', 2, 6), ('b', 1, 7), ('c', 1, 8)",
+    ]);
+    create_sqlite_file(&test_dir.file("right.sqlite"), &[
+        "create table stock (code text, region integer, quantity integer)",
+        "create unique index stock_region_code on stock (region, code)",
+        "insert into stock values ('a', 1, 5), ('a', 2, 9), ('b', 1, 7), ('c', 2, 8)",
+    ]);
+
+    let arguments = sqlite_file_arguments(&test_dir, "stock", &[]);
+    let result = processor::run_comparison(&arguments, &common::test_log());
+
+    assert_eq!(result.table_data_1.primary_key, vec!["region", "code"]);
+    assert_eq!(result.unique_table_1_rows.len(), 1);
+    assert_eq!(result.unique_table_2_rows.len(), 1);
+    assert_eq!(result.changed_rows.len(), 1);
+    assert_eq!(result.unchanged_row_count, 2);
+}
+
+#[test]
+fn write_schema_differences_to_ndjson_in_schema_only_mode() {
+    let test_dir = common::TestDir::new("write_schema_differences_to_ndjson_in_schema_only_mode");
+    create_sqlite_file(&test_dir.file("left.sqlite"), &[
+        "create table items (id integer primary key, name text, amount real)",
+        "insert into items values (1, 'alice', 10)",
+    ]);
+    create_sqlite_file(&test_dir.file("right.sqlite"), &[
+        "create table items (id integer primary key, name text)",
+        "insert into items values (2, 'bob')",
+    ]);
+
+    let output_file_name = test_dir.file("schema.ndjson");
+    let arguments = sqlite_file_arguments(
+        &test_dir,
+        "items",
+        &["-schema-only", &format!("-output={}", output_file_name)]
+    );
+    let result = processor::run_comparison(&arguments, &common::test_log());
+
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&output_file_name)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), result.schema_diff.differences.len());
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["type"], "schema_difference");
+    assert_eq!(lines[0]["kind"], "only_in_table_1");
+    assert_eq!(lines[0]["column_1"], "amount");
+    // no rows are compared in schema only mode
+    assert_eq!(result.unique_table_1_row_count(), 0);
+}
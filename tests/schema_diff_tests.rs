@@ -0,0 +1,88 @@
+use data_comparison_tool::models::{
+    column_mapping::ColumnMapping,
+    schema_diff::{
+        SchemaDiff,
+        SchemaDifferenceKind,
+    },
+    table_data::{
+        ColumnData,
+        TableData,
+    },
+};
+
+fn column(name: &str, data_type: &str, nullable: Option<bool>, max_length: Option<i64>) -> ColumnData {
+    ColumnData {
+        nullable,
+        max_length,
+        ..ColumnData::new(name.to_string(), data_type.to_string())
+    }
+}
+
+#[test]
+fn report_schema_differences(){
+    let table_1 = TableData::new(
+        "customers".to_string(),
+        vec![
+            column("id", "INT", Some(false), None),
+            column("name", "varchar(20)", Some(false), Some(20)),
+            column("balance", "INT", Some(true), None),
+            column("legacy_flag", "TEXT", None, None),
+        ],
+        vec!["id".to_string()],
+    );
+    let table_2 = TableData::new(
+        "customers_copy".to_string(),
+        vec![
+            column("customer_id", "INT", Some(false), None),
+            column("name", "VARCHAR(40)", Some(true), Some(40)),
+            column("balance", "REAL", None, None),
+        ],
+        vec!["customer_id".to_string()],
+    );
+    let column_mapping = ColumnMapping::new(&table_1, &table_2, &[]);
+
+    let schema_diff = SchemaDiff::new(&table_1, &table_2, &column_mapping);
+    let kinds: Vec<&SchemaDifferenceKind> = schema_diff.differences.iter().map(|difference| &difference.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            &SchemaDifferenceKind::Key,
+            &SchemaDifferenceKind::OnlyInTable1,
+            &SchemaDifferenceKind::Nullability,
+            &SchemaDifferenceKind::Length,
+            &SchemaDifferenceKind::Type,
+        ]
+    );
+    assert_eq!(
+        schema_diff.differences[3].describe("customers", "customers_copy"),
+        "column name holds 20 characters in customers and name holds 40 in customers_copy"
+    );
+}
+
+#[test]
+fn compare_types_by_family_and_keys_after_mapping(){
+    // the same table read from mysql and from postgres
+    let table_1 = TableData::new(
+        "customers".to_string(),
+        vec![
+            column("id", "INT", Some(false), None),
+            column("name", "VARCHAR", Some(false), Some(20)),
+            column("balance", "DECIMAL", Some(true), None),
+            column("created", "DATETIME", Some(true), None),
+        ],
+        vec!["id".to_string()],
+    );
+    let table_2 = TableData::new(
+        "customers_copy".to_string(),
+        vec![
+            column("customer_id", "bigint", Some(false), None),
+            column("name", "character varying", Some(false), Some(20)),
+            column("balance", "numeric", Some(true), None),
+            column("created", "timestamp without time zone", Some(true), None),
+        ],
+        vec!["customer_id".to_string()],
+    );
+    let column_mapping = ColumnMapping::new(&table_1, &table_2, &[("id".to_string(), "customer_id".to_string())]);
+
+    assert!(SchemaDiff::new(&table_1, &table_2, &column_mapping).is_empty());
+}
@@ -0,0 +1,81 @@
+use data_comparison_tool::{
+    datastore::sql_patch::{
+        patch_statements,
+        value_to_sql_literal,
+    },
+    interface::argument_parser::SqlDialect,
+    models::{
+        changed_row::{
+            ChangedRow,
+            ColumnDifference,
+        },
+        column_mapping::ColumnPair,
+        comparison_data::ComparisonData,
+        result_row::ResultRow,
+        table_data::TableData,
+        value::Value,
+    },
+};
+
+#[test]
+fn escape_literals_per_dialect(){
+    let value = Value::Text("it's a \\ test".to_string());
+    assert_eq!(value_to_sql_literal(&value, &SqlDialect::MySql), "'it''s a \\\\ test'");
+    assert_eq!(value_to_sql_literal(&value, &SqlDialect::Sqlite), "'it''s a \\ test'");
+    assert_eq!(value_to_sql_literal(&Value::Null, &SqlDialect::MySql), "NULL");
+    assert_eq!(value_to_sql_literal(&Value::Bytes(vec![0, 255]), &SqlDialect::Sqlite), "X'00FF'");
+}
+
+#[test]
+fn generate_statements_against_table_2_columns(){
+    let unique_row = ResultRow::new(
+        vec!["id".to_string(), "name".to_string(), "only_in_1".to_string()],
+        vec![Value::Int(3), Value::Text("carol".to_string()), Value::Int(1)],
+    );
+    let removed_row = ResultRow::new(
+        vec!["key".to_string(), "full_name".to_string()],
+        vec![Value::Int(4), Value::Text("dave".to_string())],
+    );
+    let changed_row = ChangedRow::new(
+        vec![("id".to_string(), Value::Int(2))],
+        vec![ColumnDifference {
+            column: "name".to_string(),
+            left_value: Value::Text("bob".to_string()),
+            right_value: Value::Text("bobby".to_string()),
+        }],
+    );
+    let mut comparison_data = ComparisonData::new(vec![unique_row], vec![removed_row], vec![changed_row]);
+    comparison_data.table_data_1 = TableData::new("people_1".to_string(), Vec::new(), vec!["id".to_string()]);
+    comparison_data.table_data_2 = TableData::new("people_2".to_string(), Vec::new(), vec!["key".to_string()]);
+    comparison_data.column_mapping.pairs.push(ColumnPair {
+        column_1: "name".to_string(),
+        column_2: "full_name".to_string(),
+    });
+
+    assert_eq!(
+        patch_statements(&comparison_data, &SqlDialect::MySql)
+            .into_iter()
+            .map(|statement| statement.sql)
+            .collect::<Vec<String>>(),
+        vec![
+            "INSERT INTO `people_2` (`key`, `full_name`) VALUES (3, 'carol');",
+            "DELETE FROM `people_2` WHERE `key` = 4;",
+            "UPDATE `people_2` SET `full_name` = 'bob' WHERE `key` = 2;",
+        ]
+    );
+}
+
+#[test]
+fn target_the_source_table_rather_than_the_staging_table(){
+    // a mysql:shop.orders source is staged under a date stamped name
+    let removed_row = ResultRow::new(vec!["id".to_string()], vec![Value::Int(7)]);
+    let mut comparison_data = ComparisonData::new(Vec::new(), vec![removed_row], Vec::new());
+    comparison_data.table_data_1 = TableData::new("orders20261018120000_1".to_string(), Vec::new(), vec!["id".to_string()]);
+    comparison_data.table_data_2 = TableData::new("orders20261018120000_2".to_string(), Vec::new(), vec!["id".to_string()]);
+    comparison_data.table_data_2.source_schema = "shop".to_string();
+    comparison_data.table_data_2.source_table = "orders".to_string();
+
+    let statements = patch_statements(&comparison_data, &SqlDialect::MySql);
+    assert_eq!(statements[0].sql, "DELETE FROM `shop`.`orders` WHERE `id` = 7;");
+    assert_eq!(comparison_data.table_data_2.source_name(), "shop.orders");
+}
@@ -0,0 +1,30 @@
+use chrono::NaiveDate;
+use data_comparison_tool::models::{
+    comparison_data::ComparisonData,
+    value::Value,
+};
+
+#[test]
+fn parse_text_using_source_type(){
+    let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+    assert_eq!(Value::from_text("2024-01-31".to_string(), "date"), Value::Date(date));
+    assert_eq!(
+        Value::from_text("2024-01-31 10:30:00".to_string(), "TIMESTAMP"),
+        Value::DateTime(date.and_hms_opt(10, 30, 0).unwrap())
+    );
+    assert_eq!(Value::from_text("12.50".to_string(), "numeric"), Value::Decimal("12.50".to_string()));
+    assert_eq!(Value::from_text("not a date".to_string(), "date"), Value::Text("not a date".to_string()));
+}
+
+#[test]
+fn null_values_have_no_string(){
+    assert_eq!(Value::Null.as_string(), None);
+    assert_eq!(Value::Int(5).as_string(), Some("5".to_string()));
+}
+
+#[test]
+fn comparison_data_can_be_sent_between_threads(){
+    let comparison_data = ComparisonData::new(Vec::new(), Vec::new(), Vec::new());
+    let handle = std::thread::spawn(move || comparison_data.clone().changed_rows.len());
+    assert_eq!(handle.join().unwrap(), 0);
+}